@@ -0,0 +1,315 @@
+use std::fmt::{self, Debug};
+#[cfg(feature = "mock")]
+use std::sync::Mutex;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use attohttpc::header::HeaderName;
+#[cfg(feature = "curl_client")]
+use curl::easy::{Easy, List};
+#[cfg(feature = "logging")]
+use log::{error, info};
+
+use crate::TwitchApiError;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HttpMethod {
+  Get,
+  Post,
+  Patch,
+  Delete,
+}
+
+impl fmt::Display for HttpMethod {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let method = match self {
+      HttpMethod::Get => "GET",
+      HttpMethod::Post => "POST",
+      HttpMethod::Patch => "PATCH",
+      HttpMethod::Delete => "DELETE",
+    };
+    write!(f, "{method}")
+  }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct HttpRequest {
+  pub method: HttpMethod,
+  pub url: String,
+  pub headers: Vec<(String, String)>,
+  pub body: Option<String>,
+}
+
+impl HttpRequest {
+  pub fn get<S: Into<String>>(url: S) -> HttpRequest {
+    HttpRequest {
+      method: HttpMethod::Get,
+      url: url.into(),
+      headers: Vec::new(),
+      body: None,
+    }
+  }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct HttpResponse {
+  pub status: u32,
+  pub headers: Vec<(String, String)>,
+  pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self
+      .headers
+      .iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+}
+
+/// Sends the HTTP requests for every api call.
+///
+/// Only transport failures are errors, any status code comes back as an
+/// [`HttpResponse`] and is handled by [`crate::TwitchHttpRequest::run`].
+pub trait HttpClient: Debug + Send + Sync {
+  fn send(&self, request: &HttpRequest) -> Result<HttpResponse, TwitchApiError>;
+}
+
+fn client() -> &'static RwLock<Arc<dyn HttpClient>> {
+  static HTTP_CLIENT: OnceLock<RwLock<Arc<dyn HttpClient>>> = OnceLock::new();
+  HTTP_CLIENT.get_or_init(|| {
+    #[cfg(any(feature = "attohttpc_client", not(feature = "curl_client")))]
+    let client: Arc<dyn HttpClient> = Arc::new(AttoHttpClient);
+    #[cfg(all(feature = "curl_client", not(feature = "attohttpc_client")))]
+    let client: Arc<dyn HttpClient> = Arc::new(CurlHttpClient);
+
+    RwLock::new(client)
+  })
+}
+
+/// The client requests are sent with unless they are given their own with
+/// [`crate::TwitchHttpRequest::client`].
+///
+/// The default is curl, or attohttpc when the `curl_client` feature is off
+/// or the `attohttpc_client` feature is on, until [`set_http_client`] is
+/// called.
+pub fn http_client() -> Arc<dyn HttpClient> {
+  match client().read() {
+    Ok(client) => client.clone(),
+    Err(poisoned) => poisoned.into_inner().clone(),
+  }
+}
+
+/// Swaps the default client for every request made from now on, on any
+/// thread.
+pub fn set_http_client(http_client: Arc<dyn HttpClient>) {
+  match client().write() {
+    Ok(mut client) => *client = http_client,
+    Err(poisoned) => *poisoned.into_inner() = http_client,
+  }
+}
+
+#[cfg(feature = "curl_client")]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct CurlHttpClient;
+
+#[cfg(feature = "curl_client")]
+impl HttpClient for CurlHttpClient {
+  fn send(&self, request: &HttpRequest) -> Result<HttpResponse, TwitchApiError> {
+    let mut data = Vec::new();
+    let mut response_headers = Vec::new();
+
+    #[cfg(feature = "logging")]
+    info!("Running curl command with:");
+    #[cfg(feature = "logging")]
+    info!("    url: {}", request.url);
+    let mut handle = Easy::new();
+    {
+      handle.url(&request.url).map_err(curl_error)?;
+      match request.method {
+        HttpMethod::Get => {}
+        HttpMethod::Post => {
+          handle.post(true).map_err(curl_error)?;
+        }
+        HttpMethod::Patch => {
+          handle.put(true).map_err(curl_error)?;
+          let _ = handle.custom_request("PATCH");
+        }
+        HttpMethod::Delete => {
+          let _ = handle.custom_request("DELETE");
+        }
+      }
+
+      if let Some(body) = &request.body {
+        handle
+          .post_fields_copy(body.as_bytes())
+          .map_err(curl_error)?;
+      }
+
+      let mut headers = List::new();
+      for (key, value) in &request.headers {
+        headers
+          .append(&format!("{}: {}", key, value))
+          .map_err(curl_error)?;
+      }
+
+      handle.http_headers(headers).map_err(curl_error)?;
+
+      let mut handle = handle.transfer();
+      // getting data back
+      // idk why its called write function
+      // that silly
+      // we are reading whats coming back
+      let _ = handle.write_function(|new_data| {
+        data.extend_from_slice(new_data);
+        Ok(new_data.len())
+      });
+
+      let _ = handle.header_function(|header| {
+        if let Some((key, value)) = String::from_utf8_lossy(header).split_once(':') {
+          response_headers.push((key.trim().to_owned(), value.trim().to_owned()));
+        }
+        true
+      });
+
+      if let Err(e) = handle.perform() {
+        #[cfg(feature = "logging")]
+        error!("Curl error: {}", e);
+        return Err(curl_error(e));
+      }
+    }
+
+    Ok(HttpResponse {
+      status: handle.response_code().unwrap_or_default(),
+      headers: response_headers,
+      body: data,
+    })
+  }
+}
+
+#[cfg(feature = "curl_client")]
+fn curl_error(error: curl::Error) -> TwitchApiError {
+  TwitchApiError::HttpError(error.to_string())
+}
+
+/// Pure rust client, no libcurl needed.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct AttoHttpClient;
+
+impl HttpClient for AttoHttpClient {
+  fn send(&self, request: &HttpRequest) -> Result<HttpResponse, TwitchApiError> {
+    let method = match request.method {
+      HttpMethod::Get => attohttpc::Method::GET,
+      HttpMethod::Post => attohttpc::Method::POST,
+      HttpMethod::Patch => attohttpc::Method::PATCH,
+      HttpMethod::Delete => attohttpc::Method::DELETE,
+    };
+
+    let mut builder = attohttpc::RequestBuilder::new(method, &request.url);
+    for (key, value) in &request.headers {
+      let key = HeaderName::from_bytes(key.as_bytes())
+        .map_err(|e| TwitchApiError::HttpError(e.to_string()))?;
+      builder = builder
+        .try_header_append(key, value.as_str())
+        .map_err(|e| TwitchApiError::HttpError(e.to_string()))?;
+    }
+
+    let response = builder
+      .bytes(request.body.as_deref().unwrap_or_default().as_bytes())
+      .send()
+      .map_err(|e| TwitchApiError::HttpError(e.to_string()))?;
+
+    let status = response.status().as_u16() as u32;
+    let headers = response
+      .headers()
+      .iter()
+      .map(|(key, value)| {
+        (
+          key.to_string(),
+          String::from_utf8_lossy(value.as_bytes()).to_string(),
+        )
+      })
+      .collect();
+    let body = response
+      .bytes()
+      .map_err(|e| TwitchApiError::HttpError(e.to_string()))?;
+
+    Ok(HttpResponse {
+      status,
+      headers,
+      body,
+    })
+  }
+}
+
+/// Answers requests from canned responses and keeps every request it was
+/// sent, for running without network access.
+#[cfg(feature = "mock")]
+#[derive(Default, Debug)]
+pub struct MockHttpClient {
+  responses: Mutex<Vec<(String, HttpResponse)>>,
+  requests: Mutex<Vec<HttpRequest>>,
+}
+
+#[cfg(feature = "mock")]
+impl MockHttpClient {
+  pub fn new() -> MockHttpClient {
+    MockHttpClient::default()
+  }
+
+  /// Responds to any url starting with `url`, the most recently added
+  /// response wins when several match.
+  pub fn respond<S: Into<String>, B: Into<String>>(&self, url: S, status: u32, body: B) {
+    self.respond_with(
+      url,
+      HttpResponse {
+        status,
+        headers: Vec::new(),
+        body: body.into().into_bytes(),
+      },
+    );
+  }
+
+  pub fn respond_with<S: Into<String>>(&self, url: S, response: HttpResponse) {
+    if let Ok(mut responses) = self.responses.lock() {
+      responses.push((url.into(), response));
+    }
+  }
+
+  pub fn requests(&self) -> Vec<HttpRequest> {
+    self
+      .requests
+      .lock()
+      .map(|requests| requests.clone())
+      .unwrap_or_default()
+  }
+}
+
+#[cfg(feature = "mock")]
+impl HttpClient for MockHttpClient {
+  fn send(&self, request: &HttpRequest) -> Result<HttpResponse, TwitchApiError> {
+    if let Ok(mut requests) = self.requests.lock() {
+      requests.push(request.clone());
+    }
+
+    let response = self.responses.lock().ok().and_then(|responses| {
+      responses
+        .iter()
+        .rev()
+        .find(|(url, _)| request.url.starts_with(url.as_str()))
+        .map(|(_, response)| response.clone())
+    });
+
+    Ok(response.unwrap_or_else(|| {
+      HttpResponse {
+        status: 404,
+        headers: Vec::new(),
+        body: format!(
+          r#"{{"error":"Not Found","status":404,"message":"No mock response for {}"}}"#,
+          request.url
+        )
+        .into_bytes(),
+      }
+    }))
+  }
+}
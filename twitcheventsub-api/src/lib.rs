@@ -2,9 +2,9 @@
 use std::{
   io::{BufRead, Read, stdin},
   net::TcpListener,
+  sync::Arc,
 };
 
-use curl::Error;
 use request::*;
 use twitcheventsub_structs::prelude::*;
 
@@ -24,20 +24,45 @@ pub const TWITCH_BOT_AUTHORISE_URL: &str = "https://id.twitch.tv/oauth2/authoriz
 pub const TWITCH_BAN_URL: &str = "https://api.twitch.tv/helix/moderation/bans";
 pub const TWITCH_DELETE_MESSAGE_URL: &str = "https://api.twitch.tv/helix/moderation/chat";
 pub const GET_AD_SCHEDULE_URL: &str = "https://api.twitch.tv/helix/channels/ads";
+pub const SNOOZE_NEXT_AD_URL: &str = "https://api.twitch.tv/helix/channels/ads/schedule/snooze";
+pub const START_COMMERCIAL_URL: &str = "https://api.twitch.tv/helix/channels/commercial";
 pub const GET_CHATTERS_URL: &str = "https://api.twitch.tv/helix/chat/chatters";
 pub const GET_CHANNEL_BADGES_URL: &str = "https://api.twitch.tv/helix/chat/badges";
 pub const GET_MODERATORS_URL: &str = "https://api.twitch.tv/helix/moderation/moderators";
+pub const GET_VIPS_URL: &str = "https://api.twitch.tv/helix/channels/vips";
 pub const GET_GLOBAL_BADGES_URL: &str = "https://api.twitch.tv/helix/chat/badges/global";
 pub const CUSTOM_REWARDS_URL: &str = "https://api.twitch.tv/helix/channel_points/custom_rewards";
 pub const GET_CLIPS_URL: &str = "https://api.twitch.tv/helix/clips";
 pub const GET_HYPE_TRAIN_URL: &str = "https://api.twitch.tv/helix/hypetrain/status";
-
+pub const GET_CHARITY_CAMPAIGN_URL: &str = "https://api.twitch.tv/helix/charity/campaigns";
+pub const GET_CHARITY_DONATIONS_URL: &str = "https://api.twitch.tv/helix/charity/donations";
+pub const UNBAN_REQUESTS_URL: &str = "https://api.twitch.tv/helix/moderation/unban_requests";
+pub const WARN_CHAT_USER_URL: &str = "https://api.twitch.tv/helix/moderation/warnings";
+pub const SEND_WHISPER_URL: &str = "https://api.twitch.tv/helix/whispers";
+pub const GET_STREAMS_URL: &str = "https://api.twitch.tv/helix/streams";
+pub const POLLS_URL: &str = "https://api.twitch.tv/helix/polls";
+pub const PREDICTIONS_URL: &str = "https://api.twitch.tv/helix/predictions";
+pub const CHANNELS_URL: &str = "https://api.twitch.tv/helix/channels";
+pub const SEARCH_CATEGORIES_URL: &str = "https://api.twitch.tv/helix/search/categories";
+pub const GET_GAMES_URL: &str = "https://api.twitch.tv/helix/games";
+
+mod http_client;
+mod pagination;
+mod rate_limit;
 mod request;
-pub use request::TwitchHttpRequest;
+#[cfg(feature = "curl_client")]
+pub use http_client::CurlHttpClient;
+#[cfg(feature = "mock")]
+pub use http_client::MockHttpClient;
+pub use http_client::{
+  AttoHttpClient, HttpClient, HttpMethod, HttpRequest, HttpResponse, http_client, set_http_client,
+};
+pub use pagination::PaginatedRequest;
+pub use rate_limit::{RateLimit, RateLimitKey, forget_rate_limit, rate_limit};
+pub use request::{MAX_QUERY_VALUES, RequestBuilder, TwitchHttpRequest, percent_encode};
 
 #[derive(Debug, PartialEq)]
 pub enum TwitchApiError {
-  CurlFailed(Error),
   MaximumWebsocketTransmissionsExceeded(String),
   TokenMissingSubscription(Box<Subscription>),
   TokenMissingUnimplementedSubscription(String),
@@ -48,37 +73,129 @@ pub enum TwitchApiError {
   HttpError(String),
   InputError(String),
   DeserialisationError(String),
+  Whisper(WhisperError),
+  /// Any unsuccessful response that isn't handled by a more specific variant,
+  /// see [`TwitchApiError::http_error_kind`].
+  Http {
+    status: u32,
+    error: String,
+    message: String,
+    url: String,
+  },
+  /// Still answered with a 429 after waiting for the rate limit to reset.
+  RateLimited {
+    message: String,
+    rate_limit: Option<RateLimit>,
+  },
+}
+
+/// Reasons twitch refuses to deliver a whisper.
+#[derive(Debug, PartialEq)]
+pub enum WhisperError {
+  /// Accounts without a verified phone number can't send whispers.
+  PhoneNumberNotVerified,
+  /// Too many whispers sent, or too many new recipients today.
+  RateLimited(String),
+  /// The sender is suspended or the recipient doesn't accept whispers from them.
+  NotAllowed(String),
+  RecipientNotFound,
+  /// Longer than twitch allows, only 500 characters for a new recipient.
+  MessageTooLong(String),
+}
+
+/// What an unsuccessful HTTP status means for the request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HttpErrorKind {
+  /// 400, a parameter was missing or invalid
+  BadRequest,
+  /// 403, the token user isn't allowed to do this
+  Forbidden,
+  /// 404, the user, reward or other resource doesn't exist
+  NotFound,
+  /// 409, such as a reward title already being used
+  Conflict,
+  /// 422, the request was understood but twitch can't act on it
+  Unprocessable,
+  /// 5xx, twitch had a problem, already retried before being returned
+  ServerError,
+  Other,
 }
 
+impl HttpErrorKind {
+  pub fn from_status(status: u32) -> HttpErrorKind {
+    match status {
+      400 => HttpErrorKind::BadRequest,
+      403 => HttpErrorKind::Forbidden,
+      404 => HttpErrorKind::NotFound,
+      409 => HttpErrorKind::Conflict,
+      422 => HttpErrorKind::Unprocessable,
+      500..=599 => HttpErrorKind::ServerError,
+      _ => HttpErrorKind::Other,
+    }
+  }
+}
+
+impl TwitchApiError {
+  pub fn http_error_kind(&self) -> Option<HttpErrorKind> {
+    match self {
+      TwitchApiError::Http { status, .. } => Some(HttpErrorKind::from_status(*status)),
+      _ => None,
+    }
+  }
+
+  /// Turns the generic errors returned from the Send Whisper endpoint into
+  /// [`WhisperError`]s, anything else is passed through untouched.
+  pub fn into_whisper_error(self) -> TwitchApiError {
+    match self {
+      TwitchApiError::RateLimited { message, .. } => {
+        TwitchApiError::Whisper(WhisperError::RateLimited(message))
+      }
+      TwitchApiError::Http { message, .. } if message.contains("verified phone number") => {
+        TwitchApiError::Whisper(WhisperError::PhoneNumberNotVerified)
+      }
+      TwitchApiError::Http {
+        status: 403,
+        message,
+        ..
+      } => TwitchApiError::Whisper(WhisperError::NotAllowed(message)),
+      TwitchApiError::Http {
+        status: 400,
+        message,
+        ..
+      } if message.contains("characters") => {
+        TwitchApiError::Whisper(WhisperError::MessageTooLong(message))
+      }
+      TwitchApiError::Http { status: 404, .. } => {
+        TwitchApiError::Whisper(WhisperError::RecipientNotFound)
+      }
+      error => error,
+    }
+  }
+}
+
+/// At most [`MAX_QUERY_VALUES`] ids and logins combined, with neither the
+/// user of the token is returned.
 pub fn get_users<I: Into<String>, S: Into<String>>(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   id: Vec<I>,
   login: Vec<S>,
 ) -> Result<String, TwitchApiError> {
-  let mut url = RequestBuilder::new();
-  if !id.is_empty() {
-    url = url.add_key_value(
-      "id",
-      id.into_iter()
-        .map(|id| id.into())
-        .collect::<Vec<String>>()
-        .join("&"),
-    );
-  }
-  if !login.is_empty() {
-    url = url.add_key_value(
-      "login",
-      login
-        .into_iter()
-        .map(|login| login.into())
-        .collect::<Vec<String>>()
-        .join("&"),
-    );
+  if id.len() + login.len() > MAX_QUERY_VALUES {
+    return Err(TwitchApiError::InputError(format!(
+      "Get users takes at most {} ids and logins.",
+      MAX_QUERY_VALUES
+    )));
   }
-  let url = url.build(GET_USERS_URL);
+
+  let url = RequestBuilder::new()
+    .add_key_values("id", id)
+    .add_key_values("login", login)
+    .build(GET_USERS_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .run()
@@ -163,6 +280,7 @@ pub fn get_authorisation_code_grant_flow_user_token<S: Into<String>, T: Into<Str
 }
 
 pub fn get_user_and_refresh_token_from_authorisation_code(
+  http_client: &Arc<dyn HttpClient>,
   client_id: &str,
   client_secret: &str,
   authorisation_code: &str,
@@ -173,11 +291,15 @@ pub fn get_user_and_refresh_token_from_authorisation_code(
     client_id, client_secret, authorisation_code, redirect_url
   );
 
-  create_user_and_refresh_token(&post_data)
+  create_user_and_refresh_token(http_client, &post_data)
 }
 
-pub fn validate_token(token: &str) -> Result<Validation, TwitchApiError> {
+pub fn validate_token(
+  http_client: &Arc<dyn HttpClient>,
+  token: &str,
+) -> Result<Validation, TwitchApiError> {
   TwitchHttpRequest::new(VALIDATION_TOKEN_URL)
+    .client(http_client)
     .header_authorisation(token, AuthType::OAuth)
     .run()
     .and_then(|data| {
@@ -251,8 +373,12 @@ pub fn open_browser(browser_url: &str, auto_open_browser: bool) -> Result<(), Tw
   //}
 }
 
-pub fn create_user_and_refresh_token(post_data: &str) -> Result<(String, String), TwitchApiError> {
+pub fn create_user_and_refresh_token(
+  http_client: &Arc<dyn HttpClient>,
+  post_data: &str,
+) -> Result<(String, String), TwitchApiError> {
   TwitchHttpRequest::new(TWITCH_TOKEN_URL)
+    .client(http_client)
     .url_encoded_content()
     .is_post(post_data)
     .run()
@@ -269,6 +395,7 @@ pub fn create_user_and_refresh_token(post_data: &str) -> Result<(String, String)
 }
 
 pub fn get_channel_emotes(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   broadcaster_id: &str,
@@ -278,6 +405,7 @@ pub fn get_channel_emotes(
     .build(GET_CHANNEL_EMOTES_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .run()
@@ -288,12 +416,14 @@ pub fn get_channel_emotes(
 }
 
 pub fn get_global_emotes(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
 ) -> Result<GlobalEmotes, TwitchApiError> {
   let url = RequestBuilder::new().build(GET_GLOBAL_EMOTES_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .run()
@@ -304,25 +434,50 @@ pub fn get_global_emotes(
 }
 
 pub fn get_emote_set(
+  http_client: &Arc<dyn HttpClient>,
   emote_set_id: &str,
   user_token: &str,
   client_id: &str,
 ) -> Result<GlobalEmotes, TwitchApiError> {
-  let url = RequestBuilder::new()
-    .add_key_value("emote_set_id", emote_set_id)
-    .build(GET_EMOTE_SETS_URL);
+  get_emote_sets(http_client, &[emote_set_id], user_token, client_id)
+}
 
-  TwitchHttpRequest::new(url)
-    .header_authorisation(user_token, AuthType::Bearer)
-    .header_client_id(client_id)
-    .run()
-    .and_then(|data| {
-      serde_json::from_str::<GlobalEmotes>(&data)
-        .map_err(|e| TwitchApiError::DeserialisationError(e.to_string()))
-    })
+/// Requests [`MAX_QUERY_VALUES`] emote sets at a time and merges the emotes.
+pub fn get_emote_sets<S: AsRef<str>>(
+  http_client: &Arc<dyn HttpClient>,
+  emote_set_ids: &[S],
+  user_token: &str,
+  client_id: &str,
+) -> Result<GlobalEmotes, TwitchApiError> {
+  let mut emote_sets = GlobalEmotes {
+    data: Vec::new(),
+    template: String::new(),
+  };
+
+  for emote_set_ids in emote_set_ids.chunks(MAX_QUERY_VALUES) {
+    let url = RequestBuilder::new()
+      .add_key_values("emote_set_id", emote_set_ids.iter().map(|id| id.as_ref()))
+      .build(GET_EMOTE_SETS_URL);
+
+    let emotes = TwitchHttpRequest::new(url)
+      .client(http_client)
+      .header_authorisation(user_token, AuthType::Bearer)
+      .header_client_id(client_id)
+      .run()
+      .and_then(|data| {
+        serde_json::from_str::<GlobalEmotes>(&data)
+          .map_err(|e| TwitchApiError::DeserialisationError(e.to_string()))
+      })?;
+
+    emote_sets.data.extend(emotes.data);
+    emote_sets.template = emotes.template;
+  }
+
+  Ok(emote_sets)
 }
 
 pub fn get_ad_schedule(
+  http_client: &Arc<dyn HttpClient>,
   broadcaster_id: &str,
   access_token: &str,
   client_id: &str,
@@ -332,12 +487,63 @@ pub fn get_ad_schedule(
     .build(GET_AD_SCHEDULE_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(access_token, AuthType::Bearer)
     .header_client_id(client_id)
     .run()
 }
 
+/// Twitch runs commercials of at most 180 seconds, the broadcaster must be
+/// live.
+pub fn start_commercial(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+  length_secs: u32,
+) -> Result<String, TwitchApiError> {
+  if length_secs == 0 || length_secs > 180 {
+    return Err(TwitchApiError::InputError(String::from(
+      "Commercial length must be between 1 and 180 seconds.",
+    )));
+  }
+
+  TwitchHttpRequest::new(START_COMMERCIAL_URL)
+    .client(http_client)
+    .json_content()
+    .full_auth(user_token, client_id)
+    .is_post(
+      serde_json::to_string(&StartCommercial {
+        broadcaster_id: broadcaster_id.to_owned(),
+        length: length_secs,
+      })
+      .unwrap(),
+    )
+    .run()
+}
+
+/// Pushes the next ad back 5 minutes, using up one of the snoozes in
+/// [`AdDetails::snooze_count`].
+pub fn snooze_next_ad(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+) -> Result<String, TwitchApiError> {
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .build(SNOOZE_NEXT_AD_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .is_post("")
+    .run()
+}
+
 pub fn get_chatters(
+  http_client: &Arc<dyn HttpClient>,
   broadcaster_id: &str,
   moderator_id: &str,
   access_token: &str,
@@ -349,12 +555,29 @@ pub fn get_chatters(
     .build(GET_CHATTERS_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(access_token, AuthType::Bearer)
     .header_client_id(client_id)
     .run()
 }
 
+/// Every chatter, following the pagination cursor as the iterator is used.
+pub fn get_chatters_all(
+  broadcaster_id: &str,
+  moderator_id: &str,
+  access_token: &str,
+  client_id: &str,
+) -> PaginatedRequest<User> {
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .add_key_value("moderator_id", moderator_id)
+    .build(GET_CHATTERS_URL);
+
+  PaginatedRequest::new(url, access_token, client_id).page_size(1000)
+}
+
 pub fn send_chat_message(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   sender_id: &str,
@@ -362,6 +585,7 @@ pub fn send_chat_message(
   message: &str,
 ) -> Result<String, TwitchApiError> {
   send_chat_message_with_reply(
+    http_client,
     user_token,
     client_id,
     sender_id,
@@ -372,6 +596,7 @@ pub fn send_chat_message(
 }
 
 pub fn send_chat_message_with_reply(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   sender_id: &str,
@@ -386,6 +611,7 @@ pub fn send_chat_message_with_reply(
   }
 
   TwitchHttpRequest::new(SEND_MESSAGE_URL)
+    .client(http_client)
     .json_content()
     .full_auth(user_token, client_id)
     .is_post(
@@ -401,6 +627,7 @@ pub fn send_chat_message_with_reply(
 }
 
 pub fn send_announcement<P: Into<String>>(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   sender_account_id: &str,
@@ -420,6 +647,7 @@ pub fn send_announcement<P: Into<String>>(
     .build(SEND_ANNOUNCEMENT_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .json_content()
     .full_auth(user_token, client_id)
     .is_post(
@@ -433,6 +661,7 @@ pub fn send_announcement<P: Into<String>>(
 }
 
 pub fn send_shoutout(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   moderator_id: &str,
@@ -446,6 +675,7 @@ pub fn send_shoutout(
     .build(SEND_SHOUTOUT_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .json_content()
@@ -454,6 +684,7 @@ pub fn send_shoutout(
 }
 
 pub fn delete_message(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   sender_id: &str,
@@ -467,13 +698,16 @@ pub fn delete_message(
     .build(TWITCH_DELETE_MESSAGE_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .is_delete()
     .run()
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn timeout_user(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   moderator_id: &str,
@@ -498,6 +732,7 @@ pub fn timeout_user(
   let post_data = serde_json::to_string(&post_data).unwrap();
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .json_content()
@@ -506,6 +741,7 @@ pub fn timeout_user(
 }
 
 pub fn get_channel_badges(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   broadcaster_id: &str,
@@ -515,21 +751,28 @@ pub fn get_channel_badges(
     .build(GET_CHANNEL_BADGES_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .run()
 }
 
-pub fn get_global_badges(user_token: &str, client_id: &str) -> Result<String, TwitchApiError> {
+pub fn get_global_badges(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+) -> Result<String, TwitchApiError> {
   let url = RequestBuilder::new().build(GET_GLOBAL_BADGES_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .run()
 }
 
 pub fn get_moderators(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   broadcaster_id: &str,
@@ -539,12 +782,55 @@ pub fn get_moderators(
     .build(GET_MODERATORS_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .run()
 }
 
+pub fn get_moderators_all(
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+) -> PaginatedRequest<User> {
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .build(GET_MODERATORS_URL);
+
+  PaginatedRequest::new(url, user_token, client_id).page_size(100)
+}
+
+pub fn get_vips(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+) -> Result<String, TwitchApiError> {
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .build(GET_VIPS_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .run()
+}
+
+pub fn get_vips_all(
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+) -> PaginatedRequest<User> {
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .build(GET_VIPS_URL);
+
+  PaginatedRequest::new(url, user_token, client_id).page_size(100)
+}
+
 pub fn get_custom_rewards(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   broadcaster_id: &str,
@@ -553,12 +839,14 @@ pub fn get_custom_rewards(
     .add_key_value("broadcaster_id", broadcaster_id)
     .build(CUSTOM_REWARDS_URL);
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .run()
 }
 
 pub fn update_custom_rewards(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   broadcaster_id: &str,
@@ -571,6 +859,7 @@ pub fn update_custom_rewards(
     .build(CUSTOM_REWARDS_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .json_content()
@@ -579,6 +868,7 @@ pub fn update_custom_rewards(
 }
 
 pub fn create_custom_reward(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   broadcaster_id: &str,
@@ -589,6 +879,7 @@ pub fn create_custom_reward(
     .build(CUSTOM_REWARDS_URL);
   let data = serde_json::to_string(&custom_reward_data).unwrap();
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .json_content()
@@ -597,6 +888,7 @@ pub fn create_custom_reward(
 }
 
 pub fn delete_custom_reward(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   broadcaster_id: &str,
@@ -608,6 +900,7 @@ pub fn delete_custom_reward(
     .build(CUSTOM_REWARDS_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .is_delete()
@@ -615,6 +908,7 @@ pub fn delete_custom_reward(
 }
 
 pub fn get_clips(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   broadcaster_id: &str,
@@ -623,12 +917,51 @@ pub fn get_clips(
     .add_key_value("broadcaster_id", broadcaster_id)
     .build(GET_CLIPS_URL);
   TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .run()
 }
 
+/// At most [`MAX_QUERY_VALUES`] clip ids.
+pub fn get_clips_by_id<S: AsRef<str>>(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  clip_ids: &[S],
+) -> Result<String, TwitchApiError> {
+  if clip_ids.len() > MAX_QUERY_VALUES {
+    return Err(TwitchApiError::InputError(format!(
+      "Get clips takes at most {} ids.",
+      MAX_QUERY_VALUES
+    )));
+  }
+
+  let url = RequestBuilder::new()
+    .add_key_values("id", clip_ids.iter().map(|id| id.as_ref()))
+    .build(GET_CLIPS_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .run()
+}
+
+pub fn get_clips_all(
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+) -> PaginatedRequest<ClipDetails> {
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .build(GET_CLIPS_URL);
+
+  PaginatedRequest::new(url, user_token, client_id).page_size(100)
+}
+
 pub fn get_hype_train_status(
+  http_client: &Arc<dyn HttpClient>,
   user_token: &str,
   client_id: &str,
   broadcaster_id: &str,
@@ -638,6 +971,497 @@ pub fn get_hype_train_status(
     .build(GET_HYPE_TRAIN_URL);
 
   TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .run()
+}
+
+pub fn get_charity_campaign(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+) -> Result<String, TwitchApiError> {
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .build(GET_CHARITY_CAMPAIGN_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .run()
+}
+
+pub fn get_charity_campaign_donations(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+) -> Result<String, TwitchApiError> {
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .build(GET_CHARITY_DONATIONS_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .run()
+}
+
+pub fn get_unban_requests(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+  moderator_id: &str,
+  status: UnbanRequestStatus,
+) -> Result<String, TwitchApiError> {
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .add_key_value("moderator_id", moderator_id)
+    .add_key_value("status", status.as_str())
+    .build(UNBAN_REQUESTS_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .run()
+}
+
+/// Only [`UnbanRequestStatus::Approved`] and [`UnbanRequestStatus::Denied`]
+/// are accepted by twitch when resolving a request.
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_unban_request(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+  moderator_id: &str,
+  unban_request_id: &str,
+  status: UnbanRequestStatus,
+  resolution_text: Option<&str>,
+) -> Result<String, TwitchApiError> {
+  let mut url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .add_key_value("moderator_id", moderator_id)
+    .add_key_value("unban_request_id", unban_request_id)
+    .add_key_value("status", status.as_str());
+
+  if let Some(resolution_text) = resolution_text {
+    url = url.add_key_value("resolution_text", resolution_text);
+  }
+
+  TwitchHttpRequest::new(url.build(UNBAN_REQUESTS_URL))
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .is_patch("")
+    .run()
+}
+
+pub fn warn_chat_user(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  moderator_id: &str,
+  broadcaster_id: &str,
+  user_id: &str,
+  reason: &str,
+) -> Result<String, TwitchApiError> {
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .add_key_value("moderator_id", moderator_id)
+    .build(WARN_CHAT_USER_URL);
+
+  let post_data = SendWarnRequest {
+    data: WarnRequestData {
+      user_id: user_id.to_owned(),
+      reason: reason.to_owned(),
+    },
+  };
+
+  let post_data = serde_json::to_string(&post_data).unwrap();
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .json_content()
+    .is_post(post_data)
+    .run()
+}
+
+/// Whispers can be up to 10000 characters, but only 500 for the first whisper
+/// to a new recipient, which comes back as [`WhisperError::MessageTooLong`].
+pub fn send_whisper(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  from_user_id: &str,
+  to_user_id: &str,
+  message: &str,
+) -> Result<String, TwitchApiError> {
+  if message.chars().count() > 10000 {
+    return Err(TwitchApiError::InputError(String::from(
+      "Whisper Length is too long.",
+    )));
+  }
+
+  let url = RequestBuilder::new()
+    .add_key_value("from_user_id", from_user_id)
+    .add_key_value("to_user_id", to_user_id)
+    .build(SEND_WHISPER_URL);
+
+  let request = TwitchHttpRequest::new(url)
+    .client(http_client)
+    .json_content()
+    .full_auth(user_token, client_id)
+    .is_post(
+      serde_json::to_string(&SendWhisper {
+        message: message.to_owned(),
+      })
+      .unwrap(),
+    )
+    .without_token_refresh();
+
+  // Twitch also answers with a 401 when the sender has no verified phone
+  // number, only ask for a token refresh once that has been ruled out
+  request
+    .run()
+    .map_err(|error| match error.into_whisper_error() {
+      TwitchApiError::Http { status: 401, .. } => {
+        TwitchApiError::TokenRequiresRefreshing(Box::new(request.clone()))
+      }
+      error => error,
+    })
+}
+
+pub fn get_streams(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  user_id: &str,
+) -> Result<String, TwitchApiError> {
+  get_streams_for_users(
+    http_client,
+    user_token,
+    client_id,
+    &[user_id],
+    &[] as &[&str],
+  )
+}
+
+/// At most [`MAX_QUERY_VALUES`] user ids and logins combined, only live
+/// streams are returned.
+pub fn get_streams_for_users<I: AsRef<str>, S: AsRef<str>>(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  user_ids: &[I],
+  user_logins: &[S],
+) -> Result<String, TwitchApiError> {
+  let count = user_ids.len() + user_logins.len();
+  if count > MAX_QUERY_VALUES {
+    return Err(TwitchApiError::InputError(format!(
+      "Get streams takes at most {} user ids and logins.",
+      MAX_QUERY_VALUES
+    )));
+  }
+
+  // Without either twitch returns whichever streams are most popular
+  if count == 0 {
+    return Err(TwitchApiError::InputError(String::from(
+      "Get streams needs at least one user id or login.",
+    )));
+  }
+
+  // Only 20 streams come back unless asked for more
+  let url = RequestBuilder::new()
+    .add_key_values("user_id", user_ids.iter().map(|id| id.as_ref()))
+    .add_key_values("user_login", user_logins.iter().map(|login| login.as_ref()))
+    .add_key_value("first", count.to_string())
+    .build(GET_STREAMS_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .run()
+}
+
+pub fn create_poll(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  poll: &CreatePoll,
+) -> Result<String, TwitchApiError> {
+  TwitchHttpRequest::new(POLLS_URL)
+    .client(http_client)
+    .json_content()
+    .full_auth(user_token, client_id)
+    .is_post(serde_json::to_string(poll).unwrap())
+    .run()
+}
+
+/// The most recent polls first, at most 20 `poll_ids`. With no ids the
+/// broadcaster's polls from the last 90 days are returned.
+pub fn get_polls<S: AsRef<str>>(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+  poll_ids: &[S],
+) -> Result<String, TwitchApiError> {
+  if poll_ids.len() > 20 {
+    return Err(TwitchApiError::InputError(String::from(
+      "Get polls takes at most 20 ids.",
+    )));
+  }
+
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .add_key_values("id", poll_ids.iter().map(|id| id.as_ref()))
+    .build(POLLS_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .run()
+}
+
+/// Only [`PollStatus::Terminated`] and [`PollStatus::Archived`] are accepted
+/// by twitch when ending a poll.
+pub fn end_poll(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+  poll_id: &str,
+  status: PollStatus,
+) -> Result<String, TwitchApiError> {
+  if !matches!(status, PollStatus::Terminated | PollStatus::Archived) {
+    return Err(TwitchApiError::InputError(format!(
+      "A poll can't be ended with the status {}.",
+      status.as_str()
+    )));
+  }
+
+  TwitchHttpRequest::new(POLLS_URL)
+    .client(http_client)
+    .json_content()
+    .full_auth(user_token, client_id)
+    .is_patch(
+      serde_json::to_string(&EndPoll {
+        broadcaster_id: broadcaster_id.to_owned(),
+        id: poll_id.to_owned(),
+        status,
+      })
+      .unwrap(),
+    )
+    .run()
+}
+
+pub fn create_prediction(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  prediction: &CreatePrediction,
+) -> Result<String, TwitchApiError> {
+  TwitchHttpRequest::new(PREDICTIONS_URL)
+    .client(http_client)
+    .json_content()
+    .full_auth(user_token, client_id)
+    .is_post(serde_json::to_string(prediction).unwrap())
+    .run()
+}
+
+/// The most recent predictions first, at most 25 `prediction_ids`. With no
+/// ids the broadcaster's predictions from the last 90 days are returned.
+pub fn get_predictions<S: AsRef<str>>(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+  prediction_ids: &[S],
+) -> Result<String, TwitchApiError> {
+  if prediction_ids.len() > 25 {
+    return Err(TwitchApiError::InputError(String::from(
+      "Get predictions takes at most 25 ids.",
+    )));
+  }
+
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .add_key_values("id", prediction_ids.iter().map(|id| id.as_ref()))
+    .build(PREDICTIONS_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .run()
+}
+
+/// Locks, resolves or cancels a prediction, `winning_outcome_id` is required
+/// when resolving.
+pub fn end_prediction(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+  prediction_id: &str,
+  status: PredictionStatus,
+  winning_outcome_id: Option<&str>,
+) -> Result<String, TwitchApiError> {
+  match (status, winning_outcome_id) {
+    (PredictionStatus::Active, _) => {
+      return Err(TwitchApiError::InputError(String::from(
+        "A prediction can't be ended with the status ACTIVE.",
+      )));
+    }
+    (PredictionStatus::Resolved, None) => {
+      return Err(TwitchApiError::InputError(String::from(
+        "Resolving a prediction needs the winning outcome id.",
+      )));
+    }
+    _ => {}
+  }
+
+  TwitchHttpRequest::new(PREDICTIONS_URL)
+    .client(http_client)
+    .json_content()
+    .full_auth(user_token, client_id)
+    .is_patch(
+      serde_json::to_string(&EndPrediction {
+        broadcaster_id: broadcaster_id.to_owned(),
+        id: prediction_id.to_owned(),
+        status,
+        winning_outcome_id: if status == PredictionStatus::Resolved {
+          winning_outcome_id.map(|id| id.to_owned())
+        } else {
+          None
+        },
+      })
+      .unwrap(),
+    )
+    .run()
+}
+
+/// At most [`MAX_QUERY_VALUES`] broadcaster ids.
+pub fn get_channel_information<S: AsRef<str>>(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_ids: &[S],
+) -> Result<String, TwitchApiError> {
+  if broadcaster_ids.len() > MAX_QUERY_VALUES {
+    return Err(TwitchApiError::InputError(format!(
+      "Get channel information takes at most {} broadcaster ids.",
+      MAX_QUERY_VALUES
+    )));
+  }
+
+  let url = RequestBuilder::new()
+    .add_key_values(
+      "broadcaster_id",
+      broadcaster_ids.iter().map(|id| id.as_ref()),
+    )
+    .build(CHANNELS_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .run()
+}
+
+/// Twitch responds with no content when the channel was updated.
+pub fn modify_channel_information(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  broadcaster_id: &str,
+  channel_information: &ModifyChannelInformation,
+) -> Result<String, TwitchApiError> {
+  if let Some(title) = &channel_information.title &&
+    (title.trim().is_empty() || title.chars().count() > 140)
+  {
+    return Err(TwitchApiError::InputError(String::from(
+      "Title must be between 1 and 140 characters.",
+    )));
+  }
+
+  if let Some(tags) = &channel_information.tags &&
+    (tags.len() > 10 ||
+      tags
+        .iter()
+        .any(|tag| tag.is_empty() || tag.chars().count() > 25 || tag.contains(' ')))
+  {
+    return Err(TwitchApiError::InputError(String::from(
+      "At most 10 tags of up to 25 characters without spaces.",
+    )));
+  }
+
+  let url = RequestBuilder::new()
+    .add_key_value("broadcaster_id", broadcaster_id)
+    .build(CHANNELS_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
+    .json_content()
+    .full_auth(user_token, client_id)
+    .is_patch(serde_json::to_string(channel_information).unwrap())
+    .run()
+}
+
+/// Categories whose name contains `query`, `first` is at most 100.
+pub fn search_categories(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  query: &str,
+  first: Option<u32>,
+) -> Result<String, TwitchApiError> {
+  let mut url = RequestBuilder::new().add_key_value("query", query);
+  if let Some(first) = first {
+    url = url.add_key_value("first", first.min(100).to_string());
+  }
+
+  TwitchHttpRequest::new(url.build(SEARCH_CATEGORIES_URL))
+    .client(http_client)
+    .header_authorisation(user_token, AuthType::Bearer)
+    .header_client_id(client_id)
+    .run()
+}
+
+/// At most [`MAX_QUERY_VALUES`] ids and names combined, names must match
+/// exactly.
+pub fn get_games<I: AsRef<str>, S: AsRef<str>>(
+  http_client: &Arc<dyn HttpClient>,
+  user_token: &str,
+  client_id: &str,
+  ids: &[I],
+  names: &[S],
+) -> Result<String, TwitchApiError> {
+  if ids.len() + names.len() > MAX_QUERY_VALUES {
+    return Err(TwitchApiError::InputError(format!(
+      "Get games takes at most {} ids and names.",
+      MAX_QUERY_VALUES
+    )));
+  }
+
+  let url = RequestBuilder::new()
+    .add_key_values("id", ids.iter().map(|id| id.as_ref()))
+    .add_key_values("name", names.iter().map(|name| name.as_ref()))
+    .build(GET_GAMES_URL);
+
+  TwitchHttpRequest::new(url)
+    .client(http_client)
     .header_authorisation(user_token, AuthType::Bearer)
     .header_client_id(client_id)
     .run()
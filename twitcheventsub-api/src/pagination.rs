@@ -0,0 +1,123 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use twitcheventsub_structs::prelude::Page;
+
+use crate::{AuthType, HttpClient, TwitchApiError, TwitchHttpRequest, http_client, percent_encode};
+
+/// Lazily walks every page of a Helix list endpoint by following the
+/// `after` cursor, the next page is only requested once the current one has
+/// been used up.
+///
+/// A [`TwitchApiError::TokenRequiresRefreshing`] doesn't end the iteration,
+/// call [`PaginatedRequest::update_token`] and the same page is requested
+/// again. Any other error is returned once and then the iterator ends.
+#[derive(Debug)]
+pub struct PaginatedRequest<T> {
+  url: String,
+  user_token: String,
+  client_id: String,
+  page_size: Option<u32>,
+  cursor: Option<String>,
+  items: VecDeque<T>,
+  finished: bool,
+  http_client: Arc<dyn HttpClient>,
+}
+
+impl<T: DeserializeOwned> PaginatedRequest<T> {
+  pub fn new<S: Into<String>>(url: S, user_token: &str, client_id: &str) -> PaginatedRequest<T> {
+    PaginatedRequest {
+      url: url.into(),
+      user_token: user_token.to_owned(),
+      client_id: client_id.to_owned(),
+      page_size: None,
+      cursor: None,
+      items: VecDeque::new(),
+      finished: false,
+      http_client: http_client(),
+    }
+  }
+
+  /// Requests every page with `http_client` instead of the default from
+  /// [`crate::http_client`].
+  #[must_use]
+  pub fn client(mut self, http_client: &Arc<dyn HttpClient>) -> PaginatedRequest<T> {
+    self.http_client = http_client.clone();
+    self
+  }
+
+  /// Sets `first`, the number of items per page, each endpoint has its own maximum.
+  #[must_use]
+  pub fn page_size(mut self, page_size: u32) -> PaginatedRequest<T> {
+    self.page_size = Some(page_size);
+    self
+  }
+
+  pub fn update_token(&mut self, user_token: &str) {
+    self.user_token = user_token.to_owned();
+  }
+
+  fn page_url(&self) -> String {
+    let mut url = self.url.clone();
+
+    let mut add_key_value = |key: &str, value: &str| {
+      let separator = if url.contains('?') { '&' } else { '?' };
+      url = format!("{}{}{}={}", url, separator, key, percent_encode(value));
+    };
+
+    if let Some(page_size) = self.page_size {
+      add_key_value("first", &page_size.to_string());
+    }
+
+    if let Some(cursor) = &self.cursor {
+      add_key_value("after", cursor);
+    }
+
+    url
+  }
+
+  fn fetch_page(&mut self) -> Result<(), TwitchApiError> {
+    let data = TwitchHttpRequest::new(self.page_url())
+      .client(&self.http_client)
+      .header_authorisation(&self.user_token, AuthType::Bearer)
+      .header_client_id(&self.client_id)
+      .run()?;
+
+    let page: Page<T> = serde_json::from_str(&data)
+      .map_err(|e| TwitchApiError::DeserialisationError(e.to_string()))?;
+
+    self.cursor = page
+      .pagination
+      .and_then(|pagination| pagination.cursor)
+      .filter(|cursor| !cursor.is_empty());
+    // Twitch can hand back a cursor with an empty page at the very end
+    self.finished = self.cursor.is_none() || page.data.is_empty();
+    self.items.extend(page.data);
+
+    Ok(())
+  }
+}
+
+impl<T: DeserializeOwned> Iterator for PaginatedRequest<T> {
+  type Item = Result<T, TwitchApiError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if let Some(item) = self.items.pop_front() {
+      return Some(Ok(item));
+    }
+
+    if self.finished {
+      return None;
+    }
+
+    match self.fetch_page() {
+      Ok(()) => self.items.pop_front().map(Ok),
+      Err(e @ TwitchApiError::TokenRequiresRefreshing(_)) => Some(Err(e)),
+      Err(e) => {
+        self.finished = true;
+        Some(Err(e))
+      }
+    }
+  }
+}
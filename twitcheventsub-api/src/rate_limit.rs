@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "logging")]
+use log::warn;
+
+/// How many times a request that got a 429 is tried again.
+pub const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Helix request budget for a token, from the `Ratelimit-*` response headers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+  pub limit: u32,
+  pub remaining: u32,
+  /// When the bucket is full again
+  pub reset: SystemTime,
+}
+
+impl RateLimit {
+  pub fn from_headers(headers: &[(String, String)]) -> Option<RateLimit> {
+    let header = |name: &str| {
+      headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, value)| value.trim().parse::<u64>().ok())
+    };
+
+    Some(RateLimit {
+      limit: header("Ratelimit-Limit")? as u32,
+      remaining: header("Ratelimit-Remaining")? as u32,
+      reset: UNIX_EPOCH + Duration::from_secs(header("Ratelimit-Reset")?),
+    })
+  }
+
+  pub fn time_until_reset(&self) -> Duration {
+    self
+      .reset
+      .duration_since(SystemTime::now())
+      .unwrap_or_default()
+  }
+
+  pub fn is_exhausted(&self) -> bool {
+    self.remaining == 0 && self.time_until_reset() > Duration::ZERO
+  }
+}
+
+/// Which budget a request counts against, a hash of its token so the tokens
+/// themselves aren't kept around.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RateLimitKey(u64);
+
+impl RateLimitKey {
+  pub fn from_token(token: &str) -> RateLimitKey {
+    let mut hasher = DefaultHasher::new();
+    token.hash(&mut hasher);
+    RateLimitKey(hasher.finish())
+  }
+}
+
+// Shared between every request made with the same token, on any thread.
+fn rate_limits() -> &'static Mutex<HashMap<RateLimitKey, RateLimit>> {
+  static RATE_LIMITS: OnceLock<Mutex<HashMap<RateLimitKey, RateLimit>>> = OnceLock::new();
+  RATE_LIMITS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The last known budget for the key, None until a request has been made with it.
+pub fn rate_limit(key: RateLimitKey) -> Option<RateLimit> {
+  rate_limits()
+    .lock()
+    .ok()
+    .and_then(|rate_limits| rate_limits.get(&key).copied())
+}
+
+pub(crate) fn update_rate_limit(key: RateLimitKey, rate_limit: RateLimit) {
+  if let Ok(mut rate_limits) = rate_limits().lock() {
+    // Past the reset the budget is full again, so nothing is lost by
+    // dropping them and tokens that are no longer used don't pile up
+    rate_limits.retain(|_, rate_limit| rate_limit.time_until_reset() > Duration::ZERO);
+    rate_limits.insert(key, rate_limit);
+  }
+}
+
+/// Drops what is known about a token, call when it is replaced so the old
+/// budget isn't kept around.
+pub fn forget_rate_limit(key: RateLimitKey) {
+  if let Ok(mut rate_limits) = rate_limits().lock() {
+    rate_limits.remove(&key);
+  }
+}
+
+/// Blocks until the token has budget left, then takes one request from it so
+/// requests on other threads see it before the response comes back.
+pub(crate) fn wait_for_budget(key: RateLimitKey) {
+  loop {
+    let wait = {
+      let Ok(mut rate_limits) = rate_limits().lock() else {
+        return;
+      };
+      let Some(rate_limit) = rate_limits.get_mut(&key) else {
+        return;
+      };
+
+      if rate_limit.is_exhausted() {
+        rate_limit.time_until_reset()
+      } else {
+        rate_limit.remaining = rate_limit.remaining.saturating_sub(1);
+        return;
+      }
+    };
+
+    #[cfg(feature = "logging")]
+    warn!("Helix rate limit reached, waiting {:?}", wait);
+    thread::sleep(wait);
+  }
+}
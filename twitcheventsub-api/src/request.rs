@@ -1,11 +1,29 @@
 use std::fmt::{self, Display};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
-use curl::easy::{Easy, List};
 #[cfg(feature = "logging")]
-use log::{error, info};
-use twitcheventsub_structs::prelude::{Subscription, Validation};
+use log::{error, info, warn};
+use serde::Deserialize as Deserialise;
+use twitcheventsub_structs::prelude::Subscription;
 
 use crate::TwitchApiError;
+use crate::http_client::{HttpClient, HttpMethod, HttpRequest, HttpResponse, http_client};
+use crate::rate_limit::{self, MAX_RATE_LIMIT_RETRIES, RateLimit, RateLimitKey};
+
+/// How many times a GET, or a request that opted in with
+/// [`TwitchHttpRequest::retry_server_errors`], is tried again after a 5xx.
+pub const MAX_SERVER_ERROR_RETRIES: u32 = 3;
+pub const SERVER_ERROR_BACKOFF: Duration = Duration::from_millis(500);
+
+// Body twitch sends with every failed request
+#[derive(Deserialise)]
+struct HelixError {
+  error: Option<String>,
+  status: Option<u32>,
+  message: Option<String>,
+}
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum RequestType {
@@ -53,25 +71,28 @@ impl Display for AuthType {
   }
 }
 
-impl RequestType {
-  pub fn apply(&self, handle: &mut Easy) {
-    match self {
-      RequestType::Post(data) => {
-        handle.post(true).unwrap();
-        handle.post_fields_copy(data.as_bytes()).unwrap();
-      }
-      RequestType::Delete => {
-        let _ = handle.custom_request("DELETE");
-      }
-      RequestType::Patch(data) => {
-        handle.put(true).unwrap();
-        handle.post_fields_copy(data.as_bytes()).unwrap();
-        let _ = handle.custom_request("PATCH");
+/// Helix takes at most this many values for a repeated query parameter,
+/// such as `id` on Get Users.
+pub const MAX_QUERY_VALUES: usize = 100;
+
+/// Percent encodes everything but the unreserved characters, for query keys
+/// and values.
+pub fn percent_encode(value: &str) -> String {
+  let mut encoded = String::with_capacity(value.len());
+
+  for byte in value.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+        encoded.push(byte as char)
       }
+      _ => encoded.push_str(&format!("%{:02X}", byte)),
     }
   }
+
+  encoded
 }
 
+#[derive(Default)]
 pub struct RequestBuilder {
   data: Vec<(String, String)>,
 }
@@ -90,26 +111,57 @@ impl RequestBuilder {
     self
   }
 
+  /// Repeats `key` for every value, `id=a&id=b`, which is how Helix takes
+  /// lists.
+  pub fn add_key_values<S: Into<String>, T: Into<String>, I: IntoIterator<Item = T>>(
+    mut self,
+    key: S,
+    values: I,
+  ) -> RequestBuilder {
+    let key = key.into();
+    for value in values {
+      self.data.push((key.clone(), value.into()));
+    }
+    self
+  }
+
   pub fn build<S: Into<String>>(self, url: S) -> String {
-    let mut request = url.into();
+    let url = url.into();
 
-    if !self.data.is_empty() {
-      request = format!("{}?", request);
+    if self.data.is_empty() {
+      return url;
     }
 
-    for (key, value) in self.data {
-      request = format!("{}&{}={}", request, key, value);
-    }
+    let query = self
+      .data
+      .iter()
+      .map(|(key, value)| format!("{}={}", percent_encode(key), percent_encode(value)))
+      .collect::<Vec<String>>()
+      .join("&");
 
-    request
+    format!("{}?{}", url, query)
   }
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, Debug)]
 pub struct TwitchHttpRequest {
   url: String,
   headers: Vec<Header>,
   request_type: Option<RequestType>,
+  retry_server_errors: bool,
+  refresh_token_on_unauthorised: bool,
+  http_client: Arc<dyn HttpClient>,
+}
+
+impl PartialEq for TwitchHttpRequest {
+  fn eq(&self, other: &TwitchHttpRequest) -> bool {
+    self.url == other.url &&
+      self.headers == other.headers &&
+      self.request_type == other.request_type &&
+      self.retry_server_errors == other.retry_server_errors &&
+      self.refresh_token_on_unauthorised == other.refresh_token_on_unauthorised &&
+      Arc::ptr_eq(&self.http_client, &other.http_client)
+  }
 }
 
 impl TwitchHttpRequest {
@@ -118,9 +170,20 @@ impl TwitchHttpRequest {
       url: url.into(),
       headers: Vec::new(),
       request_type: None,
+      retry_server_errors: false,
+      refresh_token_on_unauthorised: true,
+      http_client: http_client(),
     }
   }
 
+  /// Sends the request with `http_client` instead of the default from
+  /// [`crate::http_client`].
+  #[must_use]
+  pub fn client(mut self, http_client: &Arc<dyn HttpClient>) -> TwitchHttpRequest {
+    self.http_client = http_client.clone();
+    self
+  }
+
   #[must_use]
   pub fn full_auth<S: Into<String>, T: Into<String>>(
     self,
@@ -184,6 +247,22 @@ impl TwitchHttpRequest {
     self
   }
 
+  /// GETs are always retried after a 5xx, other requests only when they
+  /// are safe to send twice.
+  #[must_use]
+  pub fn retry_server_errors(mut self) -> TwitchHttpRequest {
+    self.retry_server_errors = true;
+    self
+  }
+
+  /// 401s come back as [`TwitchApiError::Http`] instead of asking for the
+  /// token to be refreshed, for endpoints that also use it for other reasons.
+  #[must_use]
+  pub fn without_token_refresh(mut self) -> TwitchHttpRequest {
+    self.refresh_token_on_unauthorised = false;
+    self
+  }
+
   pub fn update_token(&mut self, new_token: &str) {
     for header in &mut self.headers {
       if let Header::Auth((_, token)) = header {
@@ -193,89 +272,162 @@ impl TwitchHttpRequest {
     }
   }
 
-  pub fn run(&self) -> Result<String, TwitchApiError> {
-    let mut data = Vec::new();
+  fn token(&self) -> Option<&str> {
+    self.headers.iter().find_map(|header| match header {
+      Header::Auth((_, token)) => Some(token.as_str()),
+      _ => None,
+    })
+  }
 
-    #[cfg(feature = "logging")]
-    info!("Running curl command with:");
-    #[cfg(feature = "logging")]
-    info!("    url: {}", self.url);
-    let mut handle = Easy::new();
-    {
-      handle.url(&self.url).unwrap();
-      if let Some(request) = &self.request_type {
-        request.apply(&mut handle);
+  /// Runs the request, waiting out the token's Helix rate limit first and
+  /// retrying after the reset time when twitch still answers with a 429.
+  ///
+  /// Server errors on GETs are retried with an increasing delay, any other
+  /// failed status becomes a [`TwitchApiError::Http`].
+  pub fn run(&self) -> Result<String, TwitchApiError> {
+    let rate_limit_key = self.token().map(RateLimitKey::from_token);
+    let mut retries = 0;
+    let mut server_error_retries = 0;
+    let mut server_error_backoff = SERVER_ERROR_BACKOFF;
+
+    loop {
+      if let Some(key) = rate_limit_key {
+        rate_limit::wait_for_budget(key);
       }
 
-      let mut headers = List::new();
-      for header in &self.headers {
-        headers.append(&header.generate()).unwrap();
+      let response = self.perform()?;
+
+      let rate_limit = RateLimit::from_headers(&response.headers);
+      if let (Some(key), Some(rate_limit)) = (rate_limit_key, rate_limit) {
+        rate_limit::update_rate_limit(key, rate_limit);
       }
 
-      handle.http_headers(headers).unwrap();
+      if response.status == 429 {
+        if retries < MAX_RATE_LIMIT_RETRIES {
+          retries += 1;
+          let wait = rate_limit
+            .map(|rate_limit| rate_limit.time_until_reset())
+            .filter(|wait| !wait.is_zero())
+            .unwrap_or(Duration::from_secs(1));
+
+          #[cfg(feature = "logging")]
+          warn!("Helix returned 429, retrying in {:?}", wait);
+          thread::sleep(wait);
+          continue;
+        }
+
+        return Err(TwitchApiError::RateLimited {
+          message: String::from_utf8_lossy(&response.body).to_string(),
+          rate_limit,
+        });
+      }
 
-      let mut handle = handle.transfer();
-      // getting data back
-      // idk why its called write function
-      // that silly
-      // we are reading whats coming back
-      let _ = handle.write_function(|new_data| {
-        data.extend_from_slice(new_data);
-        Ok(new_data.len())
-      });
+      let can_retry = self.retry_server_errors || self.request_type.is_none();
+      if response.status >= 500 && can_retry && server_error_retries < MAX_SERVER_ERROR_RETRIES {
+        server_error_retries += 1;
 
-      if let Err(e) = handle.perform() {
         #[cfg(feature = "logging")]
-        error!("Curl error: {}", e);
-        return Err(TwitchApiError::CurlFailed(e));
+        warn!(
+          "Helix returned {}, retrying in {:?}",
+          response.status, server_error_backoff
+        );
+        thread::sleep(server_error_backoff);
+        server_error_backoff *= 2;
+        continue;
       }
+
+      return self.handle_response(response);
     }
+  }
+
+  fn perform(&self) -> Result<HttpResponse, TwitchApiError> {
+    let (method, body) = match &self.request_type {
+      None => (HttpMethod::Get, None),
+      Some(RequestType::Post(data)) => (HttpMethod::Post, Some(data.to_owned())),
+      Some(RequestType::Patch(data)) => (HttpMethod::Patch, Some(data.to_owned())),
+      Some(RequestType::Delete) => (HttpMethod::Delete, None),
+    };
+
+    let headers = self
+      .headers
+      .iter()
+      .filter_map(|header| {
+        header
+          .generate()
+          .split_once(": ")
+          .map(|(key, value)| (key.to_owned(), value.to_owned()))
+      })
+      .collect();
+
+    self.http_client.send(&HttpRequest {
+      method,
+      url: self.url.clone(),
+      headers,
+      body,
+    })
+  }
+
+  fn handle_response(&self, response: HttpResponse) -> Result<String, TwitchApiError> {
+    let data = String::from_utf8_lossy(&response.body).to_string();
+    let error = serde_json::from_str::<HelixError>(&data).ok();
 
-    let data = String::from_utf8_lossy(&data).to_string();
-    if let Ok(error) = serde_json::from_str::<Validation>(&data) {
-      if error.is_error() {
-        if error.status.unwrap() == 429 {
-          return Err(TwitchApiError::MaximumWebsocketTransmissionsExceeded(
-            error.error_msg(),
+    // Only fall back on the body when curl didn't give a status
+    let status = match response.status {
+      0 => error.as_ref().and_then(|error| error.status).unwrap_or(200),
+      status => status,
+    };
+
+    if (200..300).contains(&status) {
+      return Ok(data);
+    }
+
+    let (error, message) = error
+      .map(|error| {
+        (
+          error.error.unwrap_or_default(),
+          error.message.unwrap_or_default(),
+        )
+      })
+      .unwrap_or_else(|| (String::new(), data.clone()));
+
+    if status == 401 {
+      // Regen access token
+      // Re run the query
+
+      if message.contains("Missing scope") {
+        let scope = message.split_whitespace().nth(2).unwrap_or_default();
+        if let Some(missing_subscription) = Subscription::from_scope(scope) {
+          #[cfg(feature = "logging")]
+          error!(
+            "Token missing subscription: Subscription::{:?}",
+            missing_subscription
+          );
+          return Err(TwitchApiError::TokenMissingSubscription(Box::new(
+            missing_subscription,
+          )));
+        } else {
+          #[cfg(feature = "logging")]
+          error!("Token missing unimplemented subscription: {}", scope);
+          return Err(TwitchApiError::TokenMissingUnimplementedSubscription(
+            scope.to_owned(),
           ));
         }
-        if error.status.unwrap() == 401 {
-          // Regen access token
-          // Re run the query
-
-          let error = error.message.unwrap();
-          if error.contains("Missing scope") {
-            let scope = error.split_whitespace().nth(2).unwrap();
-            if let Some(missing_subscription) = Subscription::from_scope(scope) {
-              #[cfg(feature = "logging")]
-              error!(
-                "Token missing subscription: Subscription::{:?}",
-                missing_subscription
-              );
-              return Err(TwitchApiError::TokenMissingSubscription(Box::new(
-                missing_subscription,
-              )));
-            } else {
-              #[cfg(feature = "logging")]
-              error!("Token missing unimplemented subscription: {}", scope);
-              return Err(TwitchApiError::TokenMissingUnimplementedSubscription(
-                scope.to_owned(),
-              ));
-            }
-          } else {
-            #[cfg(feature = "logging")]
-            info!("Token requires refresing, debug: {:?}", error);
-            return Err(TwitchApiError::TokenRequiresRefreshing(Box::new(
-              self.to_owned(),
-            )));
-          }
-        }
+      } else if self.refresh_token_on_unauthorised {
         #[cfg(feature = "logging")]
-        error!("Unhandled error: {}, {}", self.url, error.error_msg());
-        return Err(TwitchApiError::InvalidOauthToken(error.error_msg()));
+        info!("Token requires refresing, debug: {:?}", message);
+        return Err(TwitchApiError::TokenRequiresRefreshing(Box::new(
+          self.to_owned(),
+        )));
       }
     }
 
-    Ok(data)
+    #[cfg(feature = "logging")]
+    error!("Http error: {}, status: {}, {}", self.url, status, message);
+    Err(TwitchApiError::Http {
+      status,
+      error,
+      message,
+      url: self.url.clone(),
+    })
   }
 }
@@ -40,6 +40,7 @@ use modules::banned::GUserBanned;
 use twitcheventsub::EventSubError;
 use twitcheventsub::ResponseType;
 use twitcheventsub::TwitchEventSubApi;
+use twitcheventsub::prelude::twitcheventsub_api::HttpRequest;
 use twitcheventsub::prelude::twitcheventsub_api::TwitchApiError;
 use twitcheventsub::prelude::twitcheventsub_tokens::TokenBuilderError;
 use twitcheventsub::prelude::twitcheventsub_tokens::TokenHandler;
@@ -50,8 +51,8 @@ mod modules;
 use std::io::Cursor;
 
 use crate::modules::{
-  GUser, GUserData, adbreak::*, cheer::*, emote::*, follow::*, getchatters::*, messages::*,
-  poll::*, prediction::*, raid::*, redeems::*, subscription::*,
+  GUser, GUserData, adbreak::*, bits::*, cheer::*, emote::*, follow::*, getchatters::*,
+  messages::*, poll::*, prediction::*, raid::*, redeems::*, subscription::*,
 };
 
 const VISUAL_ERROR: &[u8] = include_bytes!("../assets/visual_error.png");
@@ -108,8 +109,12 @@ struct TwitchEventNode {
   #[export]
   channel_cheer: bool,
   #[export]
+  channel_bits_use: bool,
+  #[export]
   channel_points_custom_reward_redeem: bool,
   #[export]
+  channel_points_custom_reward_redemption_update: bool,
+  #[export]
   channel_points_auto_reward_redeem: bool,
   #[export]
   channel_poll_begin: bool,
@@ -230,6 +235,13 @@ pub struct GdCheerContainer {
   pub data: Gd<GCheerData>,
 }
 
+#[derive(GodotClass, Debug, GodotConvert, Clone)]
+#[godot(transparent)]
+#[class(init)]
+pub struct GdBitsUseContainer {
+  pub data: Gd<GBitsUse>,
+}
+
 #[derive(GodotClass, Debug, GodotConvert, Clone)]
 #[godot(transparent)]
 #[class(init)]
@@ -352,17 +364,16 @@ impl TwitchEventNode {
         },
       )
     {
-      if let Ok(new_image_data) = attohttpc::get(url.url).send() {
-        dbg!(new_image_data.headers());
+      // Same client as the api requests, so emotes can be mocked too
+      let image_request = HttpRequest::get(url.url);
+      if let Ok(new_image_data) = self.token.http_client().send(&image_request) {
+        dbg!(&new_image_data.headers);
         let is_gif = new_image_data
-          .headers()
-          .get("content-type")
-          .unwrap()
-          .to_str()
-          .unwrap()
+          .header("content-type")
+          .unwrap_or_default()
           .contains("gif");
 
-        let data = &new_image_data.bytes().unwrap();
+        let data = &new_image_data.body;
         if is_gif {
           let mut animated_texture = AnimatedTexture::new_gd();
 
@@ -604,6 +615,27 @@ impl TwitchEventNode {
     data
   }
 
+  #[func]
+  /// Pushes the next ad back, returns false when there are no snoozes left
+  fn snooze_next_ad(&mut self) -> bool {
+    if let Some(twitch) = &mut self.twitch {
+      let id = twitch.broadcaster().id.clone();
+      return twitch.api().snooze_next_ad(&id).is_ok();
+    }
+
+    false
+  }
+
+  #[func]
+  fn start_commercial(&mut self, length_secs: u32) -> bool {
+    if let Some(twitch) = &mut self.twitch {
+      let id = twitch.broadcaster().id.clone();
+      return twitch.api().start_commercial(&id, length_secs).is_ok();
+    }
+
+    false
+  }
+
   #[func]
   /// Get user data by ids or logins. See https://dev.twitch.tv/docs/api/reference/#get-users
   fn get_users_from_self(&mut self) -> Array<Gd<GUserData>> {
@@ -690,6 +722,9 @@ impl TwitchEventNode {
   #[signal]
   fn custom_point_reward_redeem(reward: GdCustomRewardRedeemContainer);
 
+  #[signal]
+  fn custom_point_reward_redemption_update(reward: GdCustomRewardRedeemContainer);
+
   #[signal]
   fn ad_break_start(ad_break_begin: GdAdBreakBeginContainer);
 
@@ -711,6 +746,9 @@ impl TwitchEventNode {
   #[signal]
   fn cheer(cheer: GdCheerContainer);
 
+  #[signal]
+  fn bits_use(bits_use: GdBitsUseContainer);
+
   #[signal]
   fn poll_begin(poll_begin: GdPollBeginContainer);
 
@@ -996,9 +1034,15 @@ impl TwitchEventNode {
     if self.channel_cheer {
       token = token.add_subscription(Subscription::ChannelCheer);
     }
+    if self.channel_bits_use {
+      token = token.add_subscription(Subscription::ChannelBitsUse);
+    }
     if self.channel_points_custom_reward_redeem {
       token = token.add_subscription(Subscription::ChannelPointsCustomRewardRedeem);
     }
+    if self.channel_points_custom_reward_redemption_update {
+      token = token.add_subscription(Subscription::ChannelPointsCustomRewardRedemptionUpdate);
+    }
     if self.channel_points_auto_reward_redeem {
       token = token.add_subscription(Subscription::ChannelPointsAutoRewardRedeem);
     }
@@ -1170,7 +1214,9 @@ impl INode for TwitchEventNode {
       channel_gift_subscription: true,
       channel_resubscription: true,
       channel_cheer: true,
+      channel_bits_use: false,
       channel_points_custom_reward_redeem: true,
+      channel_points_custom_reward_redemption_update: false,
       channel_points_auto_reward_redeem: true,
       channel_poll_begin: false,
       channel_poll_progress: false,
@@ -1227,10 +1273,10 @@ impl INode for TwitchEventNode {
             self.token.save();
             self.twitch = Some(twitch);
           }
-          Err(EventSubError::TwitchApiError(TwitchApiError::InvalidOauthToken(error)))
-            if error.contains("are different") =>
+          Err(EventSubError::TwitchApiError(TwitchApiError::Http { message, .. }))
+            if message.contains("are different") =>
           {
-            panic!("Twitch Id doesnt match token user id: {:?}", error);
+            panic!("Twitch Id doesnt match token user id: {:?}", message);
           }
           Err(e) => match e {
             //EventSubError::InvalidOauthToken(exact_error) => {
@@ -1300,6 +1346,15 @@ impl INode for TwitchEventNode {
                 .to_variant()],
               );
             }
+            TwitchEvent::PointsCustomRewardRedemptionUpdate(custom_reward_redeem) => {
+              self.base_mut().emit_signal(
+                "custom_point_reward_redemption_update",
+                &[GdCustomRewardRedeemContainer {
+                  data: Gd::from_object(GCustomRewardRedeem::from(custom_reward_redeem)),
+                }
+                .to_variant()],
+              );
+            }
             TwitchEvent::Follow(follow_data) => {
               self.base_mut().emit_signal(
                 "follow",
@@ -1345,6 +1400,15 @@ impl INode for TwitchEventNode {
                 .to_variant()],
               );
             }
+            TwitchEvent::BitsUse(bits_use) => {
+              self.base_mut().emit_signal(
+                "bits_use",
+                &[GdBitsUseContainer {
+                  data: Gd::from_object(GBitsUse::from(bits_use)),
+                }
+                .to_variant()],
+              );
+            }
             TwitchEvent::PollBegin(begin) => {
               self.base_mut().emit_signal(
                 "poll_begin",
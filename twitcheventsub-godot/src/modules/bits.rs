@@ -0,0 +1,92 @@
+use godot::prelude::*;
+use twitcheventsub::prelude::*;
+
+use crate::modules::{GUser, messages::GMessage};
+
+#[derive(GodotClass, Debug, Clone)]
+#[class(init)]
+pub struct GPowerUpEmote {
+  #[var]
+  pub id: GString,
+  #[var]
+  pub name: GString,
+}
+
+#[derive(GodotClass, Debug, Clone)]
+#[class(init)]
+pub struct GPowerUp {
+  #[var]
+  /// Power-up type, might be "message_effect", "celebration" or "gigantify_an_emote"
+  pub kind: GString,
+  #[var]
+  pub emote: Array<Gd<GPowerUpEmote>>,
+  #[var]
+  pub message_effect_id: GString,
+}
+
+#[derive(GodotClass, Debug, Clone)]
+#[class(init)]
+pub struct GBitsUse {
+  #[var]
+  pub broadcaster: Gd<GUser>,
+  #[var]
+  pub user: Gd<GUser>,
+  #[var]
+  pub bits: u32,
+  #[var]
+  /// Bits use type, might be "cheer", "power_up" or "combo"
+  pub kind: GString,
+  #[var]
+  pub message: Array<Gd<GMessage>>,
+  #[var]
+  pub power_up: Array<Gd<GPowerUp>>,
+}
+
+impl From<PowerUpEmote> for GPowerUpEmote {
+  fn from(emote: PowerUpEmote) -> GPowerUpEmote {
+    GPowerUpEmote {
+      id: emote.id.to_godot(),
+      name: emote.name.to_godot(),
+    }
+  }
+}
+
+impl From<PowerUp> for GPowerUp {
+  fn from(power_up: PowerUp) -> GPowerUp {
+    let mut emote = Array::new();
+
+    if let Some(emote_data) = power_up.emote {
+      emote.push(&Gd::from_object(GPowerUpEmote::from(emote_data)));
+    }
+
+    GPowerUp {
+      kind: power_up.kind.to_string().to_godot(),
+      emote,
+      message_effect_id: power_up.message_effect_id.unwrap_or_default().to_godot(),
+    }
+  }
+}
+
+impl From<BitsUseData> for GBitsUse {
+  fn from(bits_use: BitsUseData) -> GBitsUse {
+    let mut message = Array::new();
+    let mut power_up = Array::new();
+
+    if let Some(message_data) = bits_use.message {
+      message.push(&Gd::from_object(GMessage::from(message_data)));
+    }
+
+    if let Some(power_up_data) = bits_use.power_up {
+      power_up.push(&Gd::from_object(GPowerUp::from(power_up_data)));
+    }
+
+    GBitsUse {
+      broadcaster: Gd::from_object(GUser::from(bits_use.broadcaster)),
+      user: Gd::from_object(GUser::from(bits_use.user)),
+      bits: bits_use.bits,
+      kind: bits_use.kind.to_string().to_godot(),
+      message,
+      power_up,
+    }
+  }
+}
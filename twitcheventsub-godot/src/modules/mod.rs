@@ -1,6 +1,7 @@
 pub mod adbreak;
 pub mod badges;
 pub mod banned;
+pub mod bits;
 pub mod cheer;
 pub mod emote;
 pub mod follow;
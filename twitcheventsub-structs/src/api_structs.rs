@@ -1,9 +1,13 @@
 use core::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize as Deserialise, Serialize as Serialise};
 use serde_with::with_prefix;
 
-use crate::prelude::{HypeTrainProgressData, User, prefix_broadcaster};
+use crate::prelude::{
+  BitsVotingData, ChannelPointsVoting, CharityAmount, Choices, HypeTrainProgressData, Outcome,
+  UnbanRequestStatus, User, parse_timestamp, prefix_broadcaster,
+};
 
 #[derive(Serialise, Deserialise, Debug, Default, Clone)]
 pub struct UpdateCustomReward {
@@ -233,21 +237,102 @@ pub struct AdSchedule {
   pub data: Vec<AdDetails>,
 }
 
+impl AdSchedule {
+  pub fn details(&self) -> Option<&AdDetails> {
+    self.data.first()
+  }
+
+  /// None when no ad is scheduled.
+  pub fn time_until_next_ad(&self) -> Option<Duration> {
+    self.details().and_then(AdDetails::time_until_next_ad)
+  }
+
+  pub fn is_preroll_free(&self) -> bool {
+    self.details().is_some_and(AdDetails::is_preroll_free)
+  }
+}
+
+/// Times are unix timestamps in seconds, 0 when there isn't one.
 #[derive(Serialise, Deserialise, Debug)]
 pub struct AdDetails {
   pub next_ad_at: u32,
   pub last_ad_at: u32,
+  /// Seconds
   pub duration: u32,
+  /// Seconds of preroll free time left.
   pub preroll_free_time: u32,
   pub snooze_count: u32,
   pub snooze_refresh_at: u32,
 }
 
+fn unix_time(secs: u32) -> Option<SystemTime> {
+  if secs == 0 {
+    None
+  } else {
+    Some(UNIX_EPOCH + Duration::from_secs(secs as u64))
+  }
+}
+
+impl AdDetails {
+  pub fn next_ad_time(&self) -> Option<SystemTime> {
+    unix_time(self.next_ad_at)
+  }
+
+  pub fn last_ad_time(&self) -> Option<SystemTime> {
+    unix_time(self.last_ad_at)
+  }
+
+  pub fn snooze_refresh_time(&self) -> Option<SystemTime> {
+    unix_time(self.snooze_refresh_at)
+  }
+
+  /// Zero once the ad is due, None when no ad is scheduled.
+  pub fn time_until_next_ad(&self) -> Option<Duration> {
+    self.next_ad_time().map(|next_ad| {
+      next_ad
+        .duration_since(SystemTime::now())
+        .unwrap_or_default()
+    })
+  }
+
+  pub fn time_until_snooze_refresh(&self) -> Option<Duration> {
+    self.snooze_refresh_time().map(|refresh| {
+      refresh
+        .duration_since(SystemTime::now())
+        .unwrap_or_default()
+    })
+  }
+
+  pub fn duration(&self) -> Duration {
+    Duration::from_secs(self.duration as u64)
+  }
+
+  /// Viewers joining now won't see a preroll ad.
+  pub fn is_preroll_free(&self) -> bool {
+    self.preroll_free_time > 0
+  }
+
+  pub fn preroll_free_duration(&self) -> Duration {
+    Duration::from_secs(self.preroll_free_time as u64)
+  }
+
+  pub fn can_snooze(&self) -> bool {
+    self.snooze_count > 0
+  }
+}
+
 #[derive(Serialise, Deserialise, Debug)]
 pub struct Pagination {
   pub cursor: Option<String>,
 }
 
+/// A single page from any Helix endpoint that returns a list.
+#[derive(Deserialise, Debug)]
+pub struct Page<T> {
+  pub data: Vec<T>,
+  pub pagination: Option<Pagination>,
+}
+
 #[derive(Serialise, Deserialise, Debug)]
 pub struct GetChatters {
   pub data: Vec<User>,
@@ -390,6 +475,12 @@ pub struct Moderators {
   pub pagination: Pagination,
 }
 
+#[derive(Serialise, Deserialise, Debug)]
+pub struct Vips {
+  pub data: Vec<User>,
+  pub pagination: Pagination,
+}
+
 impl From<ChannelEmoteData> for EmoteData {
   fn from(emote: ChannelEmoteData) -> EmoteData {
     EmoteData {
@@ -512,14 +603,425 @@ pub struct Clips {
   pub data: Vec<ClipDetails>,
 }
 
+#[derive(Debug, Clone, Deserialise)]
+pub struct HypeTrainRecord {
+  pub level: u32,
+  pub total: u32,
+  pub achieved_at: String,
+}
+
 #[derive(Debug, Clone, Deserialise)]
 pub struct HypeTrainData {
   pub current: Option<HypeTrainProgressData>,
-  pub all_time_high: String,
-  pub shared_all_time_high: String,
+  pub all_time_high: Option<HypeTrainRecord>,
+  pub shared_all_time_high: Option<HypeTrainRecord>,
 }
 
 #[derive(Debug, Clone, Deserialise)]
 pub struct HypeTrainStatus {
   pub data: Vec<HypeTrainData>,
 }
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct CharityCampaign {
+  pub id: String,
+  pub broadcaster_id: String,
+  pub broadcaster_login: String,
+  pub broadcaster_name: String,
+  pub charity_name: String,
+  pub charity_description: String,
+  pub charity_logo: String,
+  pub charity_website: String,
+  pub current_amount: CharityAmount,
+  pub target_amount: CharityAmount,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct CharityCampaigns {
+  pub data: Vec<CharityCampaign>,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct CharityDonation {
+  pub id: String,
+  pub campaign_id: String,
+  #[serde(flatten)]
+  pub user: User,
+  pub amount: CharityAmount,
+}
+
+#[derive(Debug, Deserialise)]
+pub struct CharityDonations {
+  pub data: Vec<CharityDonation>,
+  pub pagination: Pagination,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct UnbanRequestDetails {
+  pub id: String,
+  pub broadcaster_id: String,
+  pub broadcaster_login: String,
+  pub broadcaster_name: String,
+  /// Empty until a moderator resolves the request.
+  pub moderator_id: Option<String>,
+  pub moderator_login: Option<String>,
+  pub moderator_name: Option<String>,
+  #[serde(flatten)]
+  pub user: User,
+  pub text: String,
+  pub status: UnbanRequestStatus,
+  pub created_at: String,
+  pub resolved_at: Option<String>,
+  pub resolution_text: Option<String>,
+}
+
+#[derive(Debug, Deserialise)]
+pub struct UnbanRequests {
+  pub data: Vec<UnbanRequestDetails>,
+  pub pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct WarningDetails {
+  pub broadcaster_id: String,
+  pub user_id: String,
+  pub moderator_id: String,
+  pub reason: String,
+}
+
+#[derive(Debug, Deserialise)]
+pub struct Warnings {
+  pub data: Vec<WarningDetails>,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct StreamData {
+  pub id: String,
+  #[serde(flatten)]
+  pub user: User,
+  pub game_id: String,
+  pub game_name: String,
+  #[serde(rename = "type")]
+  pub kind: String,
+  pub title: String,
+  pub tags: Vec<String>,
+  pub viewer_count: u32,
+  pub started_at: String,
+  pub language: String,
+  pub thumbnail_url: String,
+  pub is_mature: bool,
+}
+
+impl StreamData {
+  /// Get Streams only returns live streams, but `type` is empty when twitch
+  /// has an error.
+  pub fn is_live(&self) -> bool {
+    self.kind == "live"
+  }
+
+  pub fn uptime(&self) -> Option<Duration> {
+    parse_timestamp(&self.started_at)
+      .and_then(|started_at| SystemTime::now().duration_since(started_at).ok())
+  }
+
+  /// The thumbnail url with its `{width}` and `{height}` filled in.
+  pub fn thumbnail(&self, width: u32, height: u32) -> String {
+    self
+      .thumbnail_url
+      .replace("{width}", &width.to_string())
+      .replace("{height}", &height.to_string())
+  }
+}
+
+#[derive(Debug, Deserialise)]
+pub struct Streams {
+  pub data: Vec<StreamData>,
+  pub pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct ChatMessageDropReason {
+  pub code: String,
+  pub message: String,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct SentChatMessage {
+  pub message_id: String,
+  pub is_sent: bool,
+  pub drop_reason: Option<ChatMessageDropReason>,
+}
+
+#[derive(Debug, Deserialise)]
+pub struct SentChatMessages {
+  pub data: Vec<SentChatMessage>,
+}
+
+#[derive(Serialise, Deserialise, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PollStatus {
+  Active,
+  Completed,
+  /// Ended early, the results stay visible.
+  Terminated,
+  /// Ended early and hidden from viewers.
+  Archived,
+  Moderated,
+  Invalid,
+}
+
+impl PollStatus {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      PollStatus::Active => "ACTIVE",
+      PollStatus::Completed => "COMPLETED",
+      PollStatus::Terminated => "TERMINATED",
+      PollStatus::Archived => "ARCHIVED",
+      PollStatus::Moderated => "MODERATED",
+      PollStatus::Invalid => "INVALID",
+    }
+  }
+}
+
+#[derive(Serialise, Debug, Clone)]
+pub struct CreatePollChoice {
+  pub title: String,
+}
+
+#[derive(Serialise, Debug, Clone)]
+pub struct CreatePoll {
+  pub broadcaster_id: String,
+  pub title: String,
+  pub choices: Vec<CreatePollChoice>,
+  /// Seconds
+  pub duration: u32,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub channel_points_voting_enabled: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub channel_points_per_vote: Option<u32>,
+}
+
+#[derive(Serialise, Debug, Clone)]
+pub struct EndPoll {
+  pub broadcaster_id: String,
+  pub id: String,
+  pub status: PollStatus,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct Poll {
+  pub id: String,
+  pub broadcaster_id: String,
+  pub broadcaster_login: String,
+  pub broadcaster_name: String,
+  pub title: String,
+  pub choices: Vec<Choices>,
+  pub bits_voting_enabled: bool,
+  pub bits_per_vote: u32,
+  pub channel_points_voting_enabled: bool,
+  pub channel_points_per_vote: u32,
+  pub status: PollStatus,
+  pub duration: u32,
+  pub started_at: String,
+  pub ended_at: Option<String>,
+}
+
+impl Poll {
+  pub fn bits_voting(&self) -> BitsVotingData {
+    BitsVotingData {
+      is_enabled: self.bits_voting_enabled,
+      amount_per_vote: self.bits_per_vote,
+    }
+  }
+
+  pub fn channel_points_voting(&self) -> ChannelPointsVoting {
+    ChannelPointsVoting {
+      is_enabled: self.channel_points_voting_enabled,
+      amount_per_vote: self.channel_points_per_vote,
+    }
+  }
+}
+
+#[derive(Debug, Deserialise)]
+pub struct Polls {
+  pub data: Vec<Poll>,
+  pub pagination: Option<Pagination>,
+}
+
+#[derive(Serialise, Deserialise, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PredictionStatus {
+  Active,
+  /// Points were paid out to the winning outcome.
+  Resolved,
+  /// Points were refunded.
+  #[serde(rename = "CANCELED")]
+  Cancelled,
+  /// No more predictions can be made, waiting to be resolved.
+  Locked,
+}
+
+impl PredictionStatus {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      PredictionStatus::Active => "ACTIVE",
+      PredictionStatus::Resolved => "RESOLVED",
+      PredictionStatus::Cancelled => "CANCELED",
+      PredictionStatus::Locked => "LOCKED",
+    }
+  }
+}
+
+#[derive(Serialise, Debug, Clone)]
+pub struct CreatePredictionOutcome {
+  pub title: String,
+}
+
+#[derive(Serialise, Debug, Clone)]
+pub struct CreatePrediction {
+  pub broadcaster_id: String,
+  pub title: String,
+  pub outcomes: Vec<CreatePredictionOutcome>,
+  /// Seconds
+  pub prediction_window: u32,
+}
+
+#[derive(Serialise, Debug, Clone)]
+pub struct EndPrediction {
+  pub broadcaster_id: String,
+  pub id: String,
+  pub status: PredictionStatus,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub winning_outcome_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct Prediction {
+  pub id: String,
+  pub broadcaster_id: String,
+  pub broadcaster_login: String,
+  pub broadcaster_name: String,
+  pub title: String,
+  pub winning_outcome_id: Option<String>,
+  pub outcomes: Vec<Outcome>,
+  pub prediction_window: u32,
+  pub status: PredictionStatus,
+  pub created_at: String,
+  pub ended_at: Option<String>,
+  pub locked_at: Option<String>,
+}
+
+#[derive(Debug, Deserialise)]
+pub struct Predictions {
+  pub data: Vec<Prediction>,
+  pub pagination: Option<Pagination>,
+}
+
+#[derive(Serialise, Deserialise, Debug, Clone, PartialEq)]
+pub struct ContentClassificationLabel {
+  pub id: String,
+  pub is_enabled: bool,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct ChannelInformation {
+  pub broadcaster_id: String,
+  pub broadcaster_login: String,
+  pub broadcaster_name: String,
+  pub broadcaster_language: String,
+  pub game_id: String,
+  pub game_name: String,
+  pub title: String,
+  /// Seconds
+  pub delay: u32,
+  pub tags: Vec<String>,
+  pub content_classification_labels: Vec<String>,
+  pub is_branded_content: bool,
+}
+
+#[derive(Debug, Deserialise)]
+pub struct Channels {
+  pub data: Vec<ChannelInformation>,
+}
+
+/// Only the fields that are set are changed.
+#[derive(Serialise, Debug, Default, Clone)]
+pub struct ModifyChannelInformation {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub game_id: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub broadcaster_language: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub title: Option<String>,
+  /// Seconds, partners only.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub delay: Option<u32>,
+  /// An empty list removes every tag.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub tags: Option<Vec<String>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub content_classification_labels: Option<Vec<ContentClassificationLabel>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub is_branded_content: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct Category {
+  pub id: String,
+  pub name: String,
+  pub box_art_url: String,
+}
+
+#[derive(Debug, Deserialise)]
+pub struct Categories {
+  pub data: Vec<Category>,
+  pub pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Clone, Deserialise)]
+pub struct Game {
+  pub id: String,
+  pub name: String,
+  pub box_art_url: String,
+  pub igdb_id: String,
+}
+
+#[derive(Debug, Deserialise)]
+pub struct Games {
+  pub data: Vec<Game>,
+  pub pagination: Option<Pagination>,
+}
+
+#[derive(Serialise, Debug, Clone)]
+pub struct StartCommercial {
+  pub broadcaster_id: String,
+  /// Seconds
+  pub length: u32,
+}
+
+#[derive(Deserialise, Debug, Clone)]
+pub struct Commercial {
+  /// Seconds, twitch may run a shorter commercial than requested.
+  pub length: u32,
+  pub message: String,
+  /// Seconds until another commercial can be started.
+  pub retry_after: u32,
+}
+
+#[derive(Deserialise, Debug)]
+pub struct Commercials {
+  pub data: Vec<Commercial>,
+}
+
+/// Times are unix timestamps in seconds, the same as [`AdDetails`].
+#[derive(Deserialise, Debug, Clone)]
+pub struct SnoozedAd {
+  pub snooze_count: u32,
+  pub snooze_refresh_at: u32,
+  pub next_ad_at: u32,
+}
+
+#[derive(Deserialise, Debug)]
+pub struct SnoozedAds {
+  pub data: Vec<SnoozedAd>,
+}
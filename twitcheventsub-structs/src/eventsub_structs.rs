@@ -18,6 +18,9 @@ with_prefix!(pub prefix_parent "parent_");
 with_prefix!(pub prefix_chatter "chatter_");
 with_prefix!(pub prefix_target "target_");
 with_prefix!(pub prefix_moderator "moderator_");
+with_prefix!(pub prefix_host_broadcaster "host_broadcaster_");
+with_prefix!(pub prefix_from "from_");
+with_prefix!(pub prefix_to "to_");
 
 #[derive(Serialise, Deserialise, Clone, Debug)]
 pub struct TopContributions {
@@ -42,6 +45,7 @@ pub struct HypeTrainEndData {
   pub cooldown_ends_at: String,
   #[serde(rename = "type")]
   pub kind: HypeTrainType,
+  pub is_shared_train: bool,
 }
 
 #[derive(Serialise, Deserialise, Clone, Debug)]
@@ -51,7 +55,7 @@ pub struct Broadcaster {
   pub broadcaster_user_login: String,
 }
 
-#[derive(Serialise, Deserialise, Clone, Debug)]
+#[derive(Serialise, Deserialise, Clone, Debug, PartialEq)]
 pub enum HypeTrainType {
   #[serde(rename = "treasure")]
   Treasure,
@@ -86,16 +90,36 @@ pub struct HypeTrainBeginData {
   pub broadcaster: User,
   pub total: u32,
   pub progress: u32,
+  pub goal: u32,
   pub top_contributions: Vec<TopContributions>,
   pub shared_train_participants: Vec<Broadcaster>,
   pub level: u32,
   pub started_at: String,
-  pub exires_at: String,
+  pub expires_at: String,
   pub is_shared_train: bool,
   #[serde(rename = "type")]
   pub kind: HypeTrainType,
-  all_time_high_level: u32,
-  all_time_high_total: u32,
+  pub all_time_high_level: u32,
+  pub all_time_high_total: u32,
+}
+
+impl From<HypeTrainBeginData> for HypeTrainProgressData {
+  fn from(begin: HypeTrainBeginData) -> HypeTrainProgressData {
+    HypeTrainProgressData {
+      id: begin.id,
+      broadcaster: begin.broadcaster,
+      level: begin.level,
+      total: begin.total,
+      progress: begin.progress,
+      goal: begin.goal,
+      top_contributions: begin.top_contributions,
+      shared_train_participants: begin.shared_train_participants,
+      started_at: begin.started_at,
+      expires_at: begin.expires_at,
+      is_shared_train: begin.is_shared_train,
+      kind: begin.kind,
+    }
+  }
 }
 
 #[derive(Serialise, Deserialise, Clone, Debug)]
@@ -122,9 +146,19 @@ pub struct Outcome {
   pub colour: String,
   pub users: u32,
   pub channel_points: u32,
+  /// Helix sends null when nobody has predicted this outcome.
+  #[serde(default, deserialize_with = "null_as_default")]
   pub top_predictors: Vec<TopPredictors>,
 }
 
+fn null_as_default<'de, D, T>(deserialiser: D) -> Result<T, D::Error>
+where
+  D: serde::Deserializer<'de>,
+  T: Default + Deserialise<'de>,
+{
+  Ok(Option::<T>::deserialize(deserialiser)?.unwrap_or_default())
+}
+
 #[derive(Serialise, Deserialise, Clone, Debug)]
 pub struct PredictionBeginData {
   pub id: String,
@@ -418,6 +452,46 @@ pub struct MessageData {
   pub moderator: bool,
 }
 
+impl MessageData {
+  /// True when the message was sent in another channel of the current shared chat session.
+  pub fn is_from_shared_chat(&self) -> bool {
+    self
+      .source_broadcaster
+      .id
+      .as_ref()
+      .is_some_and(|id| *id != self.broadcaster.id)
+  }
+
+  /// The broadcaster whose chat the message was originally sent in.
+  pub fn origin_broadcaster(&self) -> User {
+    match &self.source_broadcaster {
+      OptionalUser {
+        id: Some(id),
+        name: Some(name),
+        login: Some(login),
+      } => User {
+        id: id.to_owned(),
+        name: name.to_owned(),
+        login: login.to_owned(),
+      },
+      _ => self.broadcaster.clone(),
+    }
+  }
+
+  /// The message id in the channel the message was originally sent in.
+  pub fn origin_message_id(&self) -> &str {
+    self
+      .source_message_id
+      .as_deref()
+      .unwrap_or(&self.message_id)
+  }
+
+  /// The chatters badges in the channel the message was originally sent in.
+  pub fn origin_badges(&self) -> &[Badge] {
+    self.source_badges.as_deref().unwrap_or(&self.badges)
+  }
+}
+
 #[cfg_attr(
   feature = "bevy",
   derive(Serialise, Deserialise, Debug, Clone, BevyMessage)
@@ -515,6 +589,35 @@ pub struct CustomPointsRewardRedeemData {
   pub redeemed_at: String,
 }
 
+#[derive(Serialise, Deserialise, Clone, Debug, PartialEq)]
+pub enum RedemptionStatus {
+  #[serde(rename = "unfulfilled")]
+  Unfulfilled,
+  #[serde(rename = "fulfilled")]
+  Fulfilled,
+  #[serde(rename = "canceled")]
+  Cancelled,
+  #[serde(other)]
+  Unknown,
+}
+
+impl RedemptionStatus {
+  pub fn from_string(status: &str) -> RedemptionStatus {
+    match status {
+      "unfulfilled" => RedemptionStatus::Unfulfilled,
+      "fulfilled" => RedemptionStatus::Fulfilled,
+      "canceled" => RedemptionStatus::Cancelled,
+      _ => RedemptionStatus::Unknown,
+    }
+  }
+}
+
+impl CustomPointsRewardRedeemData {
+  pub fn redemption_status(&self) -> RedemptionStatus {
+    RedemptionStatus::from_string(&self.status)
+  }
+}
+
 #[cfg_attr(
   feature = "bevy",
   derive(Serialise, Deserialise, Debug, Clone, BevyMessage)
@@ -580,11 +683,452 @@ pub struct UserAction {
   pub user: User,
   pub reason: Option<String>,
   pub timeout: Option<String>,
+  pub expires_at: Option<String>,
   pub message_id: Option<String>,
   pub message_body: Option<String>,
   pub viewer_count: Option<u32>,
 }
 
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct SharedChatData {
+  pub session_id: String,
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+  #[serde(flatten, with = "prefix_host_broadcaster")]
+  pub host_broadcaster: User,
+  pub participants: Vec<Broadcaster>,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct SharedChatEndData {
+  pub session_id: String,
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+  #[serde(flatten, with = "prefix_host_broadcaster")]
+  pub host_broadcaster: User,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct RoleChangeData {
+  #[serde(flatten)]
+  pub user: User,
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct RewardLimit {
+  pub is_enabled: bool,
+  pub value: u32,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct RewardCooldown {
+  pub is_enabled: bool,
+  pub seconds: u32,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct CustomRewardData {
+  pub id: String,
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+  pub is_enabled: bool,
+  pub is_paused: bool,
+  pub is_in_stock: bool,
+  pub title: String,
+  pub cost: u32,
+  pub prompt: String,
+  pub is_user_input_required: bool,
+  pub should_redemptions_skip_request_queue: bool,
+  pub cooldown_expires_at: Option<String>,
+  pub redemptions_redeemed_current_stream: Option<u32>,
+  pub max_per_stream: RewardLimit,
+  pub max_per_user_per_stream: RewardLimit,
+  pub global_cooldown: RewardCooldown,
+  #[serde(rename = "background_color")]
+  pub background_colour: String,
+  pub image: Option<EmoteStaticImages>,
+  pub default_image: EmoteStaticImages,
+}
+
+/// Twitch sends money as a whole number plus how many of those digits are
+/// after the decimal point, so 1050 with 2 decimal places is 10.50.
+#[derive(Serialise, Deserialise, Clone, Debug, PartialEq)]
+pub struct CharityAmount {
+  pub value: i64,
+  pub decimal_places: u32,
+  pub currency: String,
+}
+
+impl CharityAmount {
+  pub fn as_f64(&self) -> f64 {
+    self.value as f64 / 10f64.powi(self.decimal_places as i32)
+  }
+
+  /// The amount without a currency, e.g. `10.50`.
+  pub fn to_decimal_string(&self) -> String {
+    let sign = if self.value < 0 { "-" } else { "" };
+    let value = self.value.unsigned_abs();
+    // Past 10^19 the divisor no longer fits, every digit is a fraction by then
+    let (whole, fraction) = match 10u64.checked_pow(self.decimal_places) {
+      Some(divisor) => (value / divisor, value % divisor),
+      None => (0, value),
+    };
+
+    if self.decimal_places == 0 {
+      format!("{}{}", sign, whole)
+    } else {
+      format!(
+        "{}{}.{:0width$}",
+        sign,
+        whole,
+        fraction,
+        width = self.decimal_places as usize
+      )
+    }
+  }
+
+  /// Symbol for the more common ISO 4217 currency codes.
+  pub fn currency_symbol(&self) -> Option<&'static str> {
+    Some(match self.currency.as_str() {
+      "USD" | "CAD" | "AUD" | "NZD" | "MXN" => "$",
+      "EUR" => "€",
+      "GBP" => "£",
+      "JPY" | "CNY" => "¥",
+      "KRW" => "₩",
+      "INR" => "₹",
+      "BRL" => "R$",
+      _ => return None,
+    })
+  }
+}
+
+impl fmt::Display for CharityAmount {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.currency_symbol() {
+      Some(symbol) => write!(f, "{}{}", symbol, self.to_decimal_string()),
+      None => write!(f, "{} {}", self.to_decimal_string(), self.currency),
+    }
+  }
+}
+
+/// Unlike the donate event, the charity campaign events name the broadcaster
+/// fields without `user`.
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct CharityCampaignStartData {
+  pub id: String,
+  pub broadcaster_id: String,
+  pub broadcaster_login: String,
+  pub broadcaster_name: String,
+  pub charity_name: String,
+  pub charity_description: String,
+  pub charity_logo: String,
+  pub charity_website: String,
+  pub current_amount: CharityAmount,
+  pub target_amount: CharityAmount,
+  pub started_at: String,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct CharityCampaignProgressData {
+  pub id: String,
+  pub broadcaster_id: String,
+  pub broadcaster_login: String,
+  pub broadcaster_name: String,
+  pub charity_name: String,
+  pub charity_description: String,
+  pub charity_logo: String,
+  pub charity_website: String,
+  pub current_amount: CharityAmount,
+  pub target_amount: CharityAmount,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct CharityCampaignStopData {
+  pub id: String,
+  pub broadcaster_id: String,
+  pub broadcaster_login: String,
+  pub broadcaster_name: String,
+  pub charity_name: String,
+  pub charity_description: String,
+  pub charity_logo: String,
+  pub charity_website: String,
+  pub current_amount: CharityAmount,
+  pub target_amount: CharityAmount,
+  pub stopped_at: String,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct CharityDonationData {
+  pub id: String,
+  pub campaign_id: String,
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+  #[serde(flatten)]
+  pub user: User,
+  pub charity_name: String,
+  pub charity_description: String,
+  pub charity_logo: String,
+  pub charity_website: String,
+  pub amount: CharityAmount,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug, PartialEq)]
+pub enum UnbanRequestStatus {
+  #[serde(rename = "pending")]
+  Pending,
+  #[serde(rename = "approved")]
+  Approved,
+  #[serde(rename = "denied")]
+  Denied,
+  #[serde(rename = "acknowledged")]
+  Acknowledged,
+  #[serde(rename = "canceled")]
+  Cancelled,
+}
+
+impl UnbanRequestStatus {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      UnbanRequestStatus::Pending => "pending",
+      UnbanRequestStatus::Approved => "approved",
+      UnbanRequestStatus::Denied => "denied",
+      UnbanRequestStatus::Acknowledged => "acknowledged",
+      UnbanRequestStatus::Cancelled => "canceled",
+    }
+  }
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct UnbanRequestCreateData {
+  pub id: String,
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+  #[serde(flatten)]
+  pub user: User,
+  pub text: String,
+  pub created_at: String,
+}
+
+/// The moderator fields are sent as null when the user cancels the request.
+mod optional_moderator {
+  use serde::{Deserializer, Serializer};
+
+  use super::{OptionalUser, User, prefix_moderator};
+
+  pub fn serialize<S: Serializer>(
+    moderator: &Option<User>,
+    serialiser: S,
+  ) -> Result<S::Ok, S::Error> {
+    prefix_moderator::serialize(moderator, serialiser)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserialiser: D) -> Result<Option<User>, D::Error> {
+    let moderator: OptionalUser = prefix_moderator::deserialize(deserialiser)?;
+
+    Ok(match moderator {
+      OptionalUser {
+        id: Some(id),
+        name: Some(name),
+        login: Some(login),
+      } => Some(User { id, name, login }),
+      _ => None,
+    })
+  }
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct UnbanRequestResolveData {
+  pub id: String,
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+  #[serde(flatten, with = "optional_moderator")]
+  pub moderator: Option<User>,
+  #[serde(flatten)]
+  pub user: User,
+  pub resolution_text: Option<String>,
+  pub status: UnbanRequestStatus,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct WarningSendData {
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+  #[serde(flatten, with = "prefix_moderator")]
+  pub moderator: User,
+  #[serde(flatten)]
+  pub user: User,
+  pub reason: Option<String>,
+  pub chat_rules_cited: Option<Vec<String>>,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct WarningAcknowledgeData {
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+  #[serde(flatten)]
+  pub user: User,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug, PartialEq)]
+pub enum LowTrustStatus {
+  #[serde(rename = "none")]
+  None,
+  #[serde(rename = "active_monitoring")]
+  ActiveMonitoring,
+  #[serde(rename = "restricted")]
+  Restricted,
+  /// Anything twitch adds later.
+  #[serde(other)]
+  Unknown,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug, PartialEq)]
+pub enum SuspiciousUserType {
+  #[serde(rename = "manually_added")]
+  ManuallyAdded,
+  #[serde(rename = "ban_evader")]
+  BanEvader,
+  #[serde(rename = "banned_in_shared_channel")]
+  BannedInSharedChannel,
+  /// Anything twitch adds later.
+  #[serde(other)]
+  Unknown,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug, PartialEq)]
+pub enum BanEvasionEvaluation {
+  #[serde(rename = "possible")]
+  Possible,
+  #[serde(rename = "likely")]
+  Likely,
+  /// Also anything twitch adds later.
+  #[serde(rename = "unknown", other)]
+  Unknown,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct SuspiciousUserMessage {
+  pub message_id: String,
+  pub text: String,
+  pub fragments: Vec<Fragments>,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct SuspiciousUserMessageData {
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+  #[serde(flatten)]
+  pub user: User,
+  pub low_trust_status: LowTrustStatus,
+  pub shared_ban_channel_ids: Vec<String>,
+  pub types: Vec<SuspiciousUserType>,
+  pub ban_evasion_evaluation: BanEvasionEvaluation,
+  pub message: SuspiciousUserMessage,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct SuspiciousUserUpdateData {
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+  #[serde(flatten, with = "prefix_moderator")]
+  pub moderator: User,
+  #[serde(flatten)]
+  pub user: User,
+  pub low_trust_status: LowTrustStatus,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct Whisper {
+  pub text: String,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct WhisperData {
+  #[serde(flatten, with = "prefix_from")]
+  pub from: User,
+  #[serde(flatten, with = "prefix_to")]
+  pub to: User,
+  pub whisper_id: String,
+  pub whisper: Whisper,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug, PartialEq)]
+pub enum BitsUseType {
+  #[serde(rename = "cheer")]
+  Cheer,
+  #[serde(rename = "power_up")]
+  PowerUp,
+  #[serde(rename = "combo")]
+  Combo,
+}
+
+impl fmt::Display for BitsUseType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        BitsUseType::Cheer => "cheer",
+        BitsUseType::PowerUp => "power_up",
+        BitsUseType::Combo => "combo",
+      }
+    )
+  }
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug, PartialEq)]
+pub enum PowerUpType {
+  #[serde(rename = "message_effect")]
+  MessageEffect,
+  #[serde(rename = "celebration")]
+  Celebration,
+  #[serde(rename = "gigantify_an_emote")]
+  GigantifyAnEmote,
+}
+
+impl fmt::Display for PowerUpType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        PowerUpType::MessageEffect => "message_effect",
+        PowerUpType::Celebration => "celebration",
+        PowerUpType::GigantifyAnEmote => "gigantify_an_emote",
+      }
+    )
+  }
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct PowerUpEmote {
+  pub id: String,
+  pub name: String,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct PowerUp {
+  #[serde(rename = "type")]
+  pub kind: PowerUpType,
+  pub emote: Option<PowerUpEmote>,
+  pub message_effect_id: Option<String>,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct BitsUseData {
+  #[serde(flatten, with = "prefix_broadcaster")]
+  pub broadcaster: User,
+  #[serde(flatten)]
+  pub user: User,
+  pub bits: u32,
+  #[serde(rename = "type")]
+  pub kind: BitsUseType,
+  pub message: Option<Message>,
+  pub power_up: Option<PowerUp>,
+}
+
 #[derive(Serialise, Deserialise, Clone, Debug)]
 pub struct StreamOnlineData {
   pub id: String,
@@ -601,6 +1145,26 @@ pub struct StreamOfflineData {
   pub broadcaster: User,
 }
 
+/// A single live stream, from going online until going offline.
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct StreamSession {
+  /// The stream id twitch gave this broadcast.
+  pub id: String,
+  pub broadcaster: User,
+  pub started_at: String,
+  pub ended_at: Option<String>,
+}
+
+/// The viewer count of a live stream changed since it was last polled.
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct ViewerCount {
+  pub stream_id: String,
+  pub broadcaster: User,
+  pub viewer_count: u32,
+  /// None for the first count of a stream.
+  pub previous_viewer_count: Option<u32>,
+}
+
 #[derive(Serialise, Deserialise, Clone, Debug)]
 pub enum ModeratorAction {
   #[serde(rename = "ban")]
@@ -675,12 +1239,64 @@ pub enum ModeratorAction {
 
 #[derive(Serialise, Deserialise, Clone, Debug)]
 pub struct Followers {
-  follow_duration_minutes: u32,
+  pub follow_duration_minutes: u32,
 }
 
 #[derive(Serialise, Deserialise, Clone, Debug)]
 pub struct Slow {
-  wait_time_seconds: u32,
+  pub wait_time_seconds: u32,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug, PartialEq)]
+pub enum AutomodTermsAction {
+  #[serde(rename = "add")]
+  Add,
+  #[serde(rename = "remove")]
+  Remove,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug, PartialEq)]
+pub enum AutomodTermsList {
+  #[serde(rename = "blocked")]
+  Blocked,
+  #[serde(rename = "permitted")]
+  Permitted,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct AutomodTerms {
+  pub action: AutomodTermsAction,
+  pub list: AutomodTermsList,
+  pub terms: Vec<String>,
+  pub from_automod: bool,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct UnbanRequest {
+  pub is_approved: bool,
+  #[serde(flatten)]
+  pub user: User,
+  pub moderator_message: Option<String>,
+}
+
+#[derive(Serialise, Deserialise, Clone, Debug)]
+pub struct Warn {
+  #[serde(flatten)]
+  pub user: User,
+  pub reason: Option<String>,
+  pub chat_rules_cited: Option<Vec<String>>,
+}
+
+/// The data attached to a [`ModeratorEvent`], as chosen by its [`ModeratorAction`].
+#[derive(Clone, Debug)]
+pub enum ModeratorActionData<'a> {
+  Followers(&'a Followers),
+  Slow(&'a Slow),
+  User(&'a User),
+  UserAction(&'a UserAction),
+  AutomodTerms(&'a AutomodTerms),
+  UnbanRequest(&'a UnbanRequest),
+  Warn(&'a Warn),
 }
 
 #[derive(Serialise, Deserialise, Clone, Debug)]
@@ -706,12 +1322,76 @@ pub struct ModeratorEvent {
   pub raid: Option<UserAction>,
   pub unraid: Option<UserAction>,
   pub delete: Option<UserAction>,
-  pub automod_terms: Option<String>,         // TODO:
-  pub unban_request: Option<String>,         // TODO:
-  pub warn: Option<String>,                  // TODO:
-  pub shared_chat_ban: Option<String>,       // TODO:
-  pub shared_chat_unban: Option<String>,     // TODO:
-  pub shared_chat_timeout: Option<String>,   // TODO:
-  pub shared_chat_untimeout: Option<String>, // TODO:
-  pub shared_chat_delete: Option<String>,    // TODO:
+  pub automod_terms: Option<AutomodTerms>,
+  pub unban_request: Option<UnbanRequest>,
+  pub warn: Option<Warn>,
+  pub shared_chat_ban: Option<UserAction>,
+  pub shared_chat_unban: Option<UserAction>,
+  pub shared_chat_timeout: Option<UserAction>,
+  pub shared_chat_untimeout: Option<UserAction>,
+  pub shared_chat_delete: Option<UserAction>,
+}
+
+impl ModeratorEvent {
+  /// Returns the payload that belongs to this events action.
+  ///
+  /// Actions like `clear` or `emoteonly` carry no extra data and return None.
+  pub fn action_data(&self) -> Option<ModeratorActionData<'_>> {
+    match self.action {
+      ModeratorAction::Followers => self.followers.as_ref().map(ModeratorActionData::Followers),
+      ModeratorAction::Slow => self.slow.as_ref().map(ModeratorActionData::Slow),
+      ModeratorAction::Vip => self.vip.as_ref().map(ModeratorActionData::User),
+      ModeratorAction::Unvip => self.unvip.as_ref().map(ModeratorActionData::User),
+      ModeratorAction::Mod => self.mod_user.as_ref().map(ModeratorActionData::User),
+      ModeratorAction::Unmod => self.unmod.as_ref().map(ModeratorActionData::User),
+      ModeratorAction::Ban => self.ban.as_ref().map(ModeratorActionData::UserAction),
+      ModeratorAction::Unban => self.unban.as_ref().map(ModeratorActionData::UserAction),
+      ModeratorAction::Timeout => self.timeout.as_ref().map(ModeratorActionData::UserAction),
+      ModeratorAction::Untimeout => self.untimeout.as_ref().map(ModeratorActionData::UserAction),
+      ModeratorAction::Raid => self.raid.as_ref().map(ModeratorActionData::UserAction),
+      ModeratorAction::Unraid => self.unraid.as_ref().map(ModeratorActionData::UserAction),
+      ModeratorAction::Delete => self.delete.as_ref().map(ModeratorActionData::UserAction),
+      ModeratorAction::AddBlockedTerm |
+      ModeratorAction::AddPermittedTerm |
+      ModeratorAction::RemoveBlockedTerm |
+      ModeratorAction::RemovePermittedTerm => self
+        .automod_terms
+        .as_ref()
+        .map(ModeratorActionData::AutomodTerms),
+      ModeratorAction::ApproveUnbanRequest | ModeratorAction::DenyUnbanRequest => self
+        .unban_request
+        .as_ref()
+        .map(ModeratorActionData::UnbanRequest),
+      ModeratorAction::Warn => self.warn.as_ref().map(ModeratorActionData::Warn),
+      ModeratorAction::SharedChatBan => self
+        .shared_chat_ban
+        .as_ref()
+        .map(ModeratorActionData::UserAction),
+      ModeratorAction::SharedChatUnban => self
+        .shared_chat_unban
+        .as_ref()
+        .map(ModeratorActionData::UserAction),
+      ModeratorAction::SharedChatTimeout => self
+        .shared_chat_timeout
+        .as_ref()
+        .map(ModeratorActionData::UserAction),
+      ModeratorAction::SharedChatUntimeout => self
+        .shared_chat_untimeout
+        .as_ref()
+        .map(ModeratorActionData::UserAction),
+      ModeratorAction::SharedChatDelete => self
+        .shared_chat_delete
+        .as_ref()
+        .map(ModeratorActionData::UserAction),
+      ModeratorAction::Clear |
+      ModeratorAction::EmoteOnly |
+      ModeratorAction::EmoteOnlyOff |
+      ModeratorAction::FollowersOff |
+      ModeratorAction::UniqueChat |
+      ModeratorAction::UniqueChatOff |
+      ModeratorAction::SlowOff |
+      ModeratorAction::Subscribers |
+      ModeratorAction::SubscribersOff => None,
+    }
+  }
 }
@@ -4,10 +4,12 @@ mod api_structs;
 mod eventsub_structs;
 mod response_messages;
 mod subscriptions;
+mod timestamp;
 
 pub mod prelude {
   pub use crate::api_structs::*;
   pub use crate::eventsub_structs::*;
   pub use crate::response_messages::*;
   pub use crate::subscriptions::*;
+  pub use crate::timestamp::*;
 }
@@ -54,6 +54,17 @@ pub struct SendTimeoutRequest {
   pub data: TimeoutRequestData,
 }
 
+#[derive(Serialise, Deserialise, Debug, Clone)]
+pub struct WarnRequestData {
+  pub user_id: String,
+  pub reason: String,
+}
+
+#[derive(Serialise, Deserialise, Debug, Clone)]
+pub struct SendWarnRequest {
+  pub data: WarnRequestData,
+}
+
 #[derive(Serialise, Deserialise, Debug, Clone)]
 pub struct SendMessage {
   pub broadcaster_id: String,
@@ -62,6 +73,11 @@ pub struct SendMessage {
   pub reply_parent_message_id: Option<String>,
 }
 
+#[derive(Serialise, Deserialise, Debug, Clone)]
+pub struct SendWhisper {
+  pub message: String,
+}
+
 #[derive(Serialise, Deserialise, Debug, Clone, PartialEq)]
 pub struct Transport {
   pub method: String,
@@ -256,22 +272,204 @@ pub enum TwitchEvent {
   PredictionBegin(PredictionBeginData),
   PredictionLock(PredictionLockData),
   PredictionEnd(PredictionEndData),
-  HypeTrainProgress(HypeTrainProgressData),
   HypeTrainBegin(HypeTrainBeginData),
+  HypeTrainProgress(HypeTrainProgressData),
   HypeTrainEnd(HypeTrainEndData),
   MessageDeleted(MessageDeletedData),
   ShoutoutReceive(ShoutoutReceiveData),
   ShoutoutCreate(ShoutoutCreateData),
-  ModeratorEvent(ModeratorEvent),
+  ModeratorEvent(Box<ModeratorEvent>),
   UserBanned(UserBannedData),
+  SharedChatBegin(SharedChatData),
+  SharedChatUpdate(SharedChatData),
+  SharedChatEnd(SharedChatEndData),
+  UnbanRequestCreate(UnbanRequestCreateData),
+  UnbanRequestResolve(UnbanRequestResolveData),
+  // Suspicious user update has every warning send field plus its own
+  SuspiciousUserUpdate(SuspiciousUserUpdateData),
+  WarningSend(WarningSendData),
+  SuspiciousUserMessage(SuspiciousUserMessageData),
+  CustomRewardAdd(CustomRewardData),
+  CustomRewardUpdate(CustomRewardData),
+  CustomRewardRemove(CustomRewardData),
+  PointsCustomRewardRedemptionUpdate(CustomPointsRewardRedeemData),
+  CharityDonation(CharityDonationData),
+  CharityCampaignStart(CharityCampaignStartData),
+  CharityCampaignStop(CharityCampaignStopData),
+  CharityCampaignProgress(CharityCampaignProgressData),
+  Whisper(WhisperData),
+  BitsUse(BitsUseData),
   StreamOnline(StreamOnlineData),
+  // Only need a user and a broadcaster so they match most other payloads,
+  // keep them after every event with more fields for when the subscription
+  // isn't known.
+  ModeratorAdd(RoleChangeData),
+  ModeratorRemove(RoleChangeData),
+  VipAdd(RoleChangeData),
+  VipRemove(RoleChangeData),
+  WarningAcknowledge(WarningAcknowledgeData),
   StreamOffline(StreamOfflineData),
+  // Not sent by twitch, these are created from stream online/offline events
+  // and must stay after them so they are never deserialised.
+  SessionStarted(StreamSession),
+  SessionEnded(StreamSession),
+  // Not sent by twitch either, created by polling Get Streams.
+  ViewerCountChanged(ViewerCount),
   #[cfg(feature = "bevy")]
   Ready,
   #[cfg(feature = "bevy")]
   Finished,
 }
 
+impl TwitchEvent {
+  /// Deserialises a notification's event straight into the variant for the
+  /// subscription it was sent for, many payloads only differ by fields the
+  /// untagged enum can't tell apart. Subscriptions without their own variant
+  /// fall back to the untagged enum.
+  pub fn from_subscription<'de, D: serde::Deserializer<'de>>(
+    subscription: &Subscription,
+    event: D,
+  ) -> Result<TwitchEvent, D::Error> {
+    Ok(match subscription {
+      Subscription::ChatMessage => {
+        TwitchEvent::ChatMessage(Box::new(MessageData::deserialize(event)?))
+      }
+      Subscription::ChannelRaid => TwitchEvent::Raid(RaidData::deserialize(event)?),
+      Subscription::ChannelFollow => TwitchEvent::Follow(FollowData::deserialize(event)?),
+      Subscription::ChannelPointsCustomRewardRedeem => {
+        TwitchEvent::PointsCustomRewardRedeem(CustomPointsRewardRedeemData::deserialize(event)?)
+      }
+      Subscription::AdBreakBegin => {
+        TwitchEvent::AdBreakBegin(AdBreakBeginData::deserialize(event)?)
+      }
+      Subscription::ChannelNewSubscription => {
+        TwitchEvent::NewSubscription(NewSubscriptionData::deserialize(event)?)
+      }
+      Subscription::ChannelGiftSubscription => {
+        TwitchEvent::GiftSubscription(GiftData::deserialize(event)?)
+      }
+      Subscription::ChannelResubscription => {
+        TwitchEvent::Resubscription(ResubscriptionData::deserialize(event)?)
+      }
+      Subscription::ChannelCheer => TwitchEvent::Cheer(CheerData::deserialize(event)?),
+      Subscription::ChannelPointsAutoRewardRedeem => TwitchEvent::ChannelPointsAutoRewardRedeem(
+        ChannelPointsAutoRewardRedeemData::deserialize(event)?,
+      ),
+      Subscription::ChannelPollBegin => TwitchEvent::PollBegin(PollBeginData::deserialize(event)?),
+      Subscription::ChannelPollProgress => {
+        TwitchEvent::PollProgress(PollProgressData::deserialize(event)?)
+      }
+      Subscription::ChannelPollEnd => TwitchEvent::PollEnd(PollEndData::deserialize(event)?),
+      Subscription::ChannelPredictionBegin => {
+        TwitchEvent::PredictionBegin(PredictionBeginData::deserialize(event)?)
+      }
+      Subscription::ChannelPredictionProgress => {
+        TwitchEvent::PredictionProgress(PredictionProgressData::deserialize(event)?)
+      }
+      Subscription::ChannelPredictionLock => {
+        TwitchEvent::PredictionLock(PredictionLockData::deserialize(event)?)
+      }
+      Subscription::ChannelPredictionEnd => {
+        TwitchEvent::PredictionEnd(PredictionEndData::deserialize(event)?)
+      }
+      Subscription::ChannelHypeTrainBegin => {
+        TwitchEvent::HypeTrainBegin(HypeTrainBeginData::deserialize(event)?)
+      }
+      Subscription::ChannelHypeTrainProgress => {
+        TwitchEvent::HypeTrainProgress(HypeTrainProgressData::deserialize(event)?)
+      }
+      Subscription::ChannelHypeTrainEnd => {
+        TwitchEvent::HypeTrainEnd(HypeTrainEndData::deserialize(event)?)
+      }
+      Subscription::ChannelMessageDeleted => {
+        TwitchEvent::MessageDeleted(MessageDeletedData::deserialize(event)?)
+      }
+      Subscription::ChannelShoutoutReceive => {
+        TwitchEvent::ShoutoutReceive(ShoutoutReceiveData::deserialize(event)?)
+      }
+      Subscription::ChannelShoutoutCreate => {
+        TwitchEvent::ShoutoutCreate(ShoutoutCreateData::deserialize(event)?)
+      }
+      Subscription::ChannelModerate => {
+        TwitchEvent::ModeratorEvent(Box::new(ModeratorEvent::deserialize(event)?))
+      }
+      Subscription::ChannelUserBanned => {
+        TwitchEvent::UserBanned(UserBannedData::deserialize(event)?)
+      }
+      Subscription::ChannelSharedChatBegin => {
+        TwitchEvent::SharedChatBegin(SharedChatData::deserialize(event)?)
+      }
+      Subscription::ChannelSharedChatUpdate => {
+        TwitchEvent::SharedChatUpdate(SharedChatData::deserialize(event)?)
+      }
+      Subscription::ChannelSharedChatEnd => {
+        TwitchEvent::SharedChatEnd(SharedChatEndData::deserialize(event)?)
+      }
+      Subscription::ChannelModeratorAdd => {
+        TwitchEvent::ModeratorAdd(RoleChangeData::deserialize(event)?)
+      }
+      Subscription::ChannelModeratorRemove => {
+        TwitchEvent::ModeratorRemove(RoleChangeData::deserialize(event)?)
+      }
+      Subscription::ChannelVipAdd => TwitchEvent::VipAdd(RoleChangeData::deserialize(event)?),
+      Subscription::ChannelVipRemove => TwitchEvent::VipRemove(RoleChangeData::deserialize(event)?),
+      Subscription::ChannelPointsCustomRewardAdd => {
+        TwitchEvent::CustomRewardAdd(CustomRewardData::deserialize(event)?)
+      }
+      Subscription::ChannelPointsCustomRewardUpdate => {
+        TwitchEvent::CustomRewardUpdate(CustomRewardData::deserialize(event)?)
+      }
+      Subscription::ChannelPointsCustomRewardRemove => {
+        TwitchEvent::CustomRewardRemove(CustomRewardData::deserialize(event)?)
+      }
+      Subscription::ChannelPointsCustomRewardRedemptionUpdate => {
+        TwitchEvent::PointsCustomRewardRedemptionUpdate(CustomPointsRewardRedeemData::deserialize(
+          event,
+        )?)
+      }
+      Subscription::ChannelCharityCampaignDonate => {
+        TwitchEvent::CharityDonation(CharityDonationData::deserialize(event)?)
+      }
+      Subscription::ChannelCharityCampaignStart => {
+        TwitchEvent::CharityCampaignStart(CharityCampaignStartData::deserialize(event)?)
+      }
+      Subscription::ChannelCharityCampaignProgress => {
+        TwitchEvent::CharityCampaignProgress(CharityCampaignProgressData::deserialize(event)?)
+      }
+      Subscription::ChannelCharityCampaignStop => {
+        TwitchEvent::CharityCampaignStop(CharityCampaignStopData::deserialize(event)?)
+      }
+      Subscription::ChannelUnbanRequestCreate => {
+        TwitchEvent::UnbanRequestCreate(UnbanRequestCreateData::deserialize(event)?)
+      }
+      Subscription::ChannelUnbanRequestResolve => {
+        TwitchEvent::UnbanRequestResolve(UnbanRequestResolveData::deserialize(event)?)
+      }
+      Subscription::ChannelWarningSend => {
+        TwitchEvent::WarningSend(WarningSendData::deserialize(event)?)
+      }
+      Subscription::ChannelWarningAcknowledge => {
+        TwitchEvent::WarningAcknowledge(WarningAcknowledgeData::deserialize(event)?)
+      }
+      Subscription::ChannelSuspiciousUserMessage => {
+        TwitchEvent::SuspiciousUserMessage(SuspiciousUserMessageData::deserialize(event)?)
+      }
+      Subscription::ChannelSuspiciousUserUpdate => {
+        TwitchEvent::SuspiciousUserUpdate(SuspiciousUserUpdateData::deserialize(event)?)
+      }
+      Subscription::UserWhisperMessage => TwitchEvent::Whisper(WhisperData::deserialize(event)?),
+      Subscription::ChannelBitsUse => TwitchEvent::BitsUse(BitsUseData::deserialize(event)?),
+      Subscription::StreamOnline => {
+        TwitchEvent::StreamOnline(StreamOnlineData::deserialize(event)?)
+      }
+      Subscription::StreamOffline => {
+        TwitchEvent::StreamOffline(StreamOfflineData::deserialize(event)?)
+      }
+      _ => TwitchEvent::deserialize(event)?,
+    })
+  }
+}
+
 #[derive(Serialise, Deserialise, Debug, Clone)]
 pub struct Payload {
   pub session: Option<Session>,
@@ -324,4 +522,34 @@ impl GenericMessage {
   pub fn subscription_type(&self) -> Subscription {
     Subscription::from_string(&self.metadata.subscription_type.clone().unwrap()).unwrap()
   }
+
+  /// Parses a message from the eventsub websocket, notification events are
+  /// deserialised with [`TwitchEvent::from_subscription`].
+  pub fn from_json(message: &str) -> Result<GenericMessage, serde_json::Error> {
+    let mut message: serde_json::Value = serde_json::from_str(message)?;
+    let event = message
+      .get_mut("payload")
+      .and_then(|payload| payload.get_mut("event"))
+      .map(serde_json::Value::take)
+      .filter(|event| !event.is_null());
+
+    let mut generic_message = GenericMessage::deserialize(message)?;
+    if let Some(event) = event {
+      let subscription = generic_message
+        .metadata
+        .subscription_type
+        .as_deref()
+        .and_then(Subscription::from_string);
+      let event = match subscription {
+        Some(subscription) => TwitchEvent::from_subscription(&subscription, event)?,
+        None => TwitchEvent::deserialize(event)?,
+      };
+
+      if let Some(payload) = &mut generic_message.payload {
+        payload.event = Some(event);
+      }
+    }
+
+    Ok(generic_message)
+  }
 }
@@ -60,6 +60,29 @@ pub enum Subscription {
   ChannelModerate,
   ChatMessage,
   AdBreakBegin,
+  ChannelSharedChatBegin,
+  ChannelSharedChatUpdate,
+  ChannelSharedChatEnd,
+  ChannelModeratorAdd,
+  ChannelModeratorRemove,
+  ChannelVipAdd,
+  ChannelVipRemove,
+  ChannelPointsCustomRewardAdd,
+  ChannelPointsCustomRewardUpdate,
+  ChannelPointsCustomRewardRemove,
+  ChannelPointsCustomRewardRedemptionUpdate,
+  ChannelCharityCampaignStart,
+  ChannelCharityCampaignProgress,
+  ChannelCharityCampaignStop,
+  ChannelCharityCampaignDonate,
+  ChannelUnbanRequestCreate,
+  ChannelUnbanRequestResolve,
+  ChannelWarningSend,
+  ChannelWarningAcknowledge,
+  ChannelSuspiciousUserMessage,
+  ChannelSuspiciousUserUpdate,
+  UserWhisperMessage,
+  ChannelBitsUse,
   PermissionBanTimeoutUser,
   PermissionDeleteMessage,
   PermissionReadChatters,
@@ -82,6 +105,10 @@ pub enum Subscription {
   PermissionIRCRead,
   PermissionIRCWrite,
   PermissionWriteToChat,
+  PermissionSendWhispers,
+  PermissionManageBroadcast,
+  PermissionStartCommercial,
+  PermissionManageAds,
   StreamOnline,
   StreamOffline,
   Custom(Box<(String, String, EventSubscription)>),
@@ -114,6 +141,8 @@ impl Subscription {
       Subscription::ChannelHypeTrainEnd,
       Subscription::ChannelUserBanned,
       Subscription::ChannelMessageDeleted,
+      Subscription::StreamOnline,
+      Subscription::StreamOffline,
       Subscription::PermissionReadModerator,
       Subscription::PermissionDeleteMessage,
       Subscription::PermissionReadChatters,
@@ -185,7 +214,11 @@ impl Subscription {
         Subscription::PermissionManageUnbanRequests |
         Subscription::PermissionManageBannedUsers |
         Subscription::PermissionManageChatMessages |
-        Subscription::PermissionManageWarnings
+        Subscription::PermissionManageWarnings |
+        Subscription::PermissionSendWhispers |
+        Subscription::PermissionManageBroadcast |
+        Subscription::PermissionStartCommercial |
+        Subscription::PermissionManageAds
     )
   }
 
@@ -242,7 +275,36 @@ impl Subscription {
     PermissionWriteToChat,
     PermissionIRCRead,
     PermissionIRCWrite,
-    AdBreakBegin
+    AdBreakBegin,
+    ChannelSharedChatBegin,
+    ChannelSharedChatUpdate,
+    ChannelSharedChatEnd,
+    ChannelModeratorAdd,
+    ChannelModeratorRemove,
+    ChannelVipAdd,
+    ChannelVipRemove,
+    ChannelPointsCustomRewardAdd,
+    ChannelPointsCustomRewardUpdate,
+    ChannelPointsCustomRewardRemove,
+    ChannelPointsCustomRewardRedemptionUpdate,
+    ChannelCharityCampaignStart,
+    ChannelCharityCampaignProgress,
+    ChannelCharityCampaignStop,
+    ChannelCharityCampaignDonate,
+    ChannelUnbanRequestCreate,
+    ChannelUnbanRequestResolve,
+    ChannelWarningSend,
+    ChannelWarningAcknowledge,
+    ChannelSuspiciousUserMessage,
+    ChannelSuspiciousUserUpdate,
+    UserWhisperMessage,
+    PermissionSendWhispers,
+    PermissionManageBroadcast,
+    PermissionStartCommercial,
+    PermissionManageAds,
+    ChannelBitsUse,
+    StreamOnline,
+    StreamOffline
   });
 
   from_scope!(Subscription {
@@ -298,7 +360,36 @@ impl Subscription {
     PermissionWriteToChat,
     PermissionIRCRead,
     PermissionIRCWrite,
-    AdBreakBegin
+    AdBreakBegin,
+    ChannelSharedChatBegin,
+    ChannelSharedChatUpdate,
+    ChannelSharedChatEnd,
+    ChannelModeratorAdd,
+    ChannelModeratorRemove,
+    ChannelVipAdd,
+    ChannelVipRemove,
+    ChannelPointsCustomRewardAdd,
+    ChannelPointsCustomRewardUpdate,
+    ChannelPointsCustomRewardRemove,
+    ChannelPointsCustomRewardRedemptionUpdate,
+    ChannelCharityCampaignStart,
+    ChannelCharityCampaignProgress,
+    ChannelCharityCampaignStop,
+    ChannelCharityCampaignDonate,
+    ChannelUnbanRequestCreate,
+    ChannelUnbanRequestResolve,
+    ChannelWarningSend,
+    ChannelWarningAcknowledge,
+    ChannelSuspiciousUserMessage,
+    ChannelSuspiciousUserUpdate,
+    UserWhisperMessage,
+    PermissionSendWhispers,
+    PermissionManageBroadcast,
+    PermissionStartCommercial,
+    PermissionManageAds,
+    ChannelBitsUse,
+    StreamOnline,
+    StreamOffline
   });
 
   fn details(&self) -> (String, String, String) {
@@ -339,6 +430,7 @@ impl Subscription {
         "1",
       ),
       Subscription::ChannelCheer => ("channel.cheer", "bits:read", "1"),
+      Subscription::ChannelBitsUse => ("channel.bits.use", "bits:read", "1"),
       Subscription::ChannelPointsAutoRewardRedeem => (
         "channel.channel_points_automatic_reward_redemption.add",
         "channel:read:redemptions+channel:manage:redemptions",
@@ -416,6 +508,78 @@ impl Subscription {
         "2",
       ),
       Subscription::ChannelUserBanned => ("channel.ban", "channel:moderate", "1"),
+      Subscription::ChannelSharedChatBegin => ("channel.shared_chat.begin", "", "1"),
+      Subscription::ChannelSharedChatUpdate => ("channel.shared_chat.update", "", "1"),
+      Subscription::ChannelSharedChatEnd => ("channel.shared_chat.end", "", "1"),
+      Subscription::ChannelModeratorAdd => ("channel.moderator.add", "moderation:read", "1"),
+      Subscription::ChannelModeratorRemove => ("channel.moderator.remove", "moderation:read", "1"),
+      Subscription::ChannelVipAdd => ("channel.vip.add", "channel:read:vips", "1"),
+      Subscription::ChannelVipRemove => ("channel.vip.remove", "channel:read:vips", "1"),
+      Subscription::ChannelPointsCustomRewardAdd => (
+        "channel.channel_points_custom_reward.add",
+        "channel:read:redemptions",
+        "1",
+      ),
+      Subscription::ChannelPointsCustomRewardUpdate => (
+        "channel.channel_points_custom_reward.update",
+        "channel:read:redemptions",
+        "1",
+      ),
+      Subscription::ChannelPointsCustomRewardRemove => (
+        "channel.channel_points_custom_reward.remove",
+        "channel:read:redemptions",
+        "1",
+      ),
+      Subscription::ChannelPointsCustomRewardRedemptionUpdate => (
+        "channel.channel_points_custom_reward_redemption.update",
+        "channel:read:redemptions",
+        "1",
+      ),
+      Subscription::ChannelCharityCampaignStart => (
+        "channel.charity_campaign.start",
+        "channel:read:charity",
+        "1",
+      ),
+      Subscription::ChannelCharityCampaignProgress => (
+        "channel.charity_campaign.progress",
+        "channel:read:charity",
+        "1",
+      ),
+      Subscription::ChannelCharityCampaignStop => {
+        ("channel.charity_campaign.stop", "channel:read:charity", "1")
+      }
+      Subscription::ChannelCharityCampaignDonate => (
+        "channel.charity_campaign.donate",
+        "channel:read:charity",
+        "1",
+      ),
+      Subscription::ChannelUnbanRequestCreate => (
+        "channel.unban_request.create",
+        "moderator:read:unban_requests",
+        "1",
+      ),
+      Subscription::ChannelUnbanRequestResolve => (
+        "channel.unban_request.resolve",
+        "moderator:read:unban_requests",
+        "1",
+      ),
+      Subscription::ChannelWarningSend => ("channel.warning.send", "moderator:read:warnings", "1"),
+      Subscription::ChannelWarningAcknowledge => (
+        "channel.warning.acknowledge",
+        "moderator:read:warnings",
+        "1",
+      ),
+      Subscription::ChannelSuspiciousUserMessage => (
+        "channel.suspicious_user.message",
+        "moderator:read:suspicious_users",
+        "1",
+      ),
+      Subscription::ChannelSuspiciousUserUpdate => (
+        "channel.suspicious_user.update",
+        "moderator:read:suspicious_users",
+        "1",
+      ),
+      Subscription::UserWhisperMessage => ("user.whisper.message", "user:read:whispers", "1"),
       Subscription::ChannelMessageDeleted => ("channel.chat.message_delete", "user:read:chat", "1"),
       Subscription::PermissionBanTimeoutUser => ("", "moderator:manage:banned_users", ""),
       Subscription::PermissionDeleteMessage => ("", "moderator:manage:chat_messages", ""),
@@ -439,6 +603,10 @@ impl Subscription {
       Subscription::PermissionIRCRead => ("", "chat:read", ""),
       Subscription::PermissionIRCWrite => ("", "chat:edit", ""),
       Subscription::PermissionWriteToChat => ("", "user:write:chat", ""),
+      Subscription::PermissionSendWhispers => ("", "user:manage:whispers", ""),
+      Subscription::PermissionManageBroadcast => ("", "channel:manage:broadcast", ""),
+      Subscription::PermissionStartCommercial => ("", "channel:edit:commercial", ""),
+      Subscription::PermissionManageAds => ("", "channel:manage:ads", ""),
       Subscription::Custom(boxed) => {
         let (ref tag, ref scope, _) = **boxed;
         (tag.as_str(), scope.as_str(), "")
@@ -464,6 +632,35 @@ impl Subscription {
     self.details().2
   }
 
+  /// Narrows a channel points subscription down to a single custom reward.
+  /// Returns None for subscriptions that don't accept a reward_id condition.
+  pub fn for_reward<S: Into<String>, T: Into<String>>(
+    &self,
+    broadcaster_account_id: S,
+    reward_id: T,
+  ) -> Option<Subscription> {
+    if !matches!(
+      self,
+      Subscription::ChannelPointsCustomRewardRedeem |
+        Subscription::ChannelPointsCustomRewardRedemptionUpdate |
+        Subscription::ChannelPointsCustomRewardUpdate |
+        Subscription::ChannelPointsCustomRewardRemove
+    ) {
+      return None;
+    }
+
+    let condition = Condition::new()
+      .broadcaster_user_id(broadcaster_account_id)
+      .reward_id(reward_id);
+    let event_subscription = EventSubscription::new(self, Transport::new("")).condition(condition);
+
+    Some(Subscription::Custom(Box::new((
+      self.tag(),
+      self.required_scope(),
+      event_subscription,
+    ))))
+  }
+
   pub fn construct_data<S: Into<String>, T: Into<String>>(
     &self,
     session_id: &str,
@@ -490,11 +687,18 @@ impl Subscription {
       Subscription::ChannelMessageDeleted |
       Subscription::PermissionManageRewards |
       Subscription::ChatMessage |
+      Subscription::UserWhisperMessage |
       Subscription::UserUpdate => {
         event_subscription.condition(condition.user_id(user_id_in_access_token.to_owned()))
       }
       Subscription::ChannelShoutoutReceive |
       Subscription::ChannelShoutoutCreate |
+      Subscription::ChannelUnbanRequestCreate |
+      Subscription::ChannelUnbanRequestResolve |
+      Subscription::ChannelWarningSend |
+      Subscription::ChannelWarningAcknowledge |
+      Subscription::ChannelSuspiciousUserMessage |
+      Subscription::ChannelSuspiciousUserUpdate |
       Subscription::ChannelModerate => event_subscription
         .condition(condition.moderator_user_id(user_id_in_access_token.to_owned())),
       Subscription::ChannelNewSubscription |
@@ -502,6 +706,7 @@ impl Subscription {
       Subscription::ChannelGiftSubscription |
       Subscription::ChannelResubscription |
       Subscription::ChannelCheer |
+      Subscription::ChannelBitsUse |
       Subscription::ChannelPollBegin |
       Subscription::ChannelPollProgress |
       Subscription::ChannelPollEnd |
@@ -518,6 +723,21 @@ impl Subscription {
       Subscription::AdBreakBegin |
       Subscription::StreamOnline |
       Subscription::StreamOffline |
+      Subscription::ChannelSharedChatBegin |
+      Subscription::ChannelSharedChatUpdate |
+      Subscription::ChannelSharedChatEnd |
+      Subscription::ChannelModeratorAdd |
+      Subscription::ChannelModeratorRemove |
+      Subscription::ChannelVipAdd |
+      Subscription::ChannelVipRemove |
+      Subscription::ChannelPointsCustomRewardAdd |
+      Subscription::ChannelPointsCustomRewardUpdate |
+      Subscription::ChannelPointsCustomRewardRemove |
+      Subscription::ChannelPointsCustomRewardRedemptionUpdate |
+      Subscription::ChannelCharityCampaignStart |
+      Subscription::ChannelCharityCampaignProgress |
+      Subscription::ChannelCharityCampaignStop |
+      Subscription::ChannelCharityCampaignDonate |
       Subscription::ChannelPointsCustomRewardRedeem => event_subscription.condition(condition),
       Subscription::Custom(boxed) => boxed.2.to_owned().transport(Transport::new(session_id)),
 
@@ -0,0 +1,96 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Parses the RFC3339 timestamps twitch sends, such as
+/// `2020-07-15T17:16:11.17106713Z`, into a [`SystemTime`].
+pub fn parse_timestamp(timestamp: &str) -> Option<SystemTime> {
+  let (date, time) = timestamp.split_once(['T', 't'])?;
+
+  let mut date = date.splitn(3, '-');
+  let year: i64 = date.next()?.parse().ok()?;
+  let month: i64 = date.next()?.parse().ok()?;
+  let day: i64 = date.next()?.parse().ok()?;
+
+  let (time, offset_secs) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+    (time, 0)
+  } else {
+    let split = time.rfind(['+', '-'])?;
+    let (time, offset) = time.split_at(split);
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let (hours, minutes) = offset[1..].split_once(':')?;
+    let offset = hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60;
+    (time, sign * offset)
+  };
+
+  let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+  let mut time = time.splitn(3, ':');
+  let hour: i64 = time.next()?.parse().ok()?;
+  let minute: i64 = time.next()?.parse().ok()?;
+  let second: i64 = time.next()?.parse().ok()?;
+
+  let nanos = if fraction.is_empty() {
+    0
+  } else {
+    let digits = &fraction[..fraction.len().min(9)];
+    digits.parse::<u32>().ok()? * 10u32.pow(9 - digits.len() as u32)
+  };
+
+  if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+    return None;
+  }
+
+  let secs =
+    days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset_secs;
+
+  UNIX_EPOCH.checked_add(Duration::new(u64::try_from(secs).ok()?, nanos))
+}
+
+/// Formats a [`SystemTime`] the same way twitch does, e.g.
+/// `2020-07-15T17:16:11.171067Z`.
+pub fn format_timestamp(time: SystemTime) -> String {
+  let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+  let secs = since_epoch.as_secs() as i64;
+  let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+  let secs_of_day = secs.rem_euclid(86400);
+
+  format!(
+    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+    year,
+    month,
+    day,
+    secs_of_day / 3600,
+    secs_of_day % 3600 / 60,
+    secs_of_day % 60,
+    since_epoch.subsec_micros()
+  )
+}
+
+// Days since 1970-01-01 for a date in the proleptic gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let year_of_era = year - era * 400;
+  let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+  era * 146097 + day_of_era - 719468
+}
+
+// Inverse of days_from_civil.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+  let days = days + 719468;
+  let era = days.div_euclid(146097);
+  let day_of_era = days - era * 146097;
+  let year_of_era =
+    (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 {
+    month_index + 3
+  } else {
+    month_index - 9
+  };
+  let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+  (year, month, day)
+}
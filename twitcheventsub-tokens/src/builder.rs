@@ -1,7 +1,11 @@
-use std::{io::stdin, process::exit, sync::mpsc::Receiver};
+use std::{
+  io::stdin,
+  process::exit,
+  sync::{Arc, mpsc::Receiver},
+};
 
 use twitcheventsub_api::{
-  TwitchApiError, get_user_and_refresh_token_from_authorisation_code, validate_token,
+  HttpClient, TwitchApiError, get_user_and_refresh_token_from_authorisation_code, validate_token,
 };
 use twitcheventsub_structs::prelude::Subscription;
 
@@ -52,6 +56,7 @@ pub struct TokenHandlerBuilder {
   use_specific_account: Option<String>,
   is_bot: bool,
   override_redirect_url: Option<String>,
+  http_client: Option<Arc<dyn HttpClient>>,
   pub subscriptions: Vec<Subscription>,
 }
 
@@ -64,6 +69,7 @@ impl Default for TokenHandlerBuilder {
       use_specific_account: None,
       is_bot: false,
       override_redirect_url: None,
+      http_client: None,
       subscriptions: Vec::new(),
     }
   }
@@ -104,6 +110,13 @@ impl TokenHandlerBuilder {
     self
   }
 
+  /// The client the built token handler sends its requests with, see
+  /// [`TokenHandler::set_http_client`].
+  pub fn http_client(mut self, client: Arc<dyn HttpClient>) -> TokenHandlerBuilder {
+    self.http_client = Some(client);
+    self
+  }
+
   pub fn add_subscription(mut self, subscription: Subscription) -> TokenHandlerBuilder {
     self.subscriptions.push(subscription);
     self
@@ -129,6 +142,7 @@ impl TokenHandlerBuilder {
       user_token_env: self.env_user_token_file.clone(),
       refresh_token_env: self.env_refresh_token_file.clone(),
       subscriptions: self.subscriptions.clone(),
+      http_client: self.http_client.clone(),
       // token_channel_received: RetrievedAuthorisationCode(None),
     }
   }
@@ -244,7 +258,7 @@ impl TokenHandlerBuilder {
     EnvHandler::load_refresh_token_env(&mut token);
 
     if !token.refresh_token.is_empty() {
-      match validate_token(&token.user_token) {
+      match validate_token(&token.http_client(), &token.user_token) {
         Ok(validation) => {
           if validation.status.is_none() {
             match token.get_users(Vec::new() as Vec<String>, Vec::new() as Vec<String>) {
@@ -297,7 +311,7 @@ impl TokenHandlerBuilder {
     EnvHandler::load_refresh_token_env(&mut token);
 
     if !token.refresh_token.is_empty() {
-      match validate_token(&token.user_token) {
+      match validate_token(&token.http_client(), &token.user_token) {
         Ok(validation) => {
           if validation.status.is_none() {
             match token.get_users(Vec::new() as Vec<String>, Vec::new() as Vec<String>) {
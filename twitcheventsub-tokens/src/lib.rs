@@ -1,27 +1,40 @@
 #![allow(clippy::uninlined_format_args)]
 
 use std::{
-  sync::mpsc::{Receiver, Sender, channel},
+  mem,
+  sync::{
+    Arc,
+    mpsc::{Receiver, Sender, channel},
+  },
   thread,
 };
 
 use env_handler::EnvHandler;
 use log::{debug, warn};
+use serde::de::DeserializeOwned;
 use twitcheventsub_api::{
-  self, TwitchApiError, get_user_and_refresh_token_from_authorisation_code, validate_token,
+  self, HttpClient, MAX_QUERY_VALUES, PaginatedRequest, RateLimit, RateLimitKey, TwitchApiError,
+  get_user_and_refresh_token_from_authorisation_code, validate_token,
 };
 use twitcheventsub_structs::prelude::{
-  AdSchedule, ChannelEmotes, Clips, CreateCustomReward, CreatedCustomRewardResponse, GetChatters,
-  GetCustomRewards, GlobalEmotes, HypeTrainStatus, Moderators, Subscription, UpdateCustomReward,
-  UserDataSet,
+  AdSchedule, Categories, ChannelEmotes, ChannelInformation, Channels, CharityCampaigns,
+  CharityDonations, ClipDetails, Clips, Commercial, Commercials, CreateCustomReward,
+  CreatedCustomRewardResponse, Games, GetChatters, GetCustomRewards, GlobalEmotes, HypeTrainStatus,
+  Moderators, ModifyChannelInformation, Page, PollStatus, Polls, PredictionStatus, Predictions,
+  SnoozedAd, SnoozedAds, StreamData, Streams, Subscription, UnbanRequestStatus, UnbanRequests,
+  UpdateCustomReward, User, UserDataSet, Vips, Warnings,
 };
 
 mod builder;
 mod custom_redeems;
 mod env_handler;
+mod polls;
+mod predictions;
 
 pub use builder::{TokenBuilderError, TokenHandlerBuilder};
 pub use custom_redeems::*;
+pub use polls::*;
+pub use predictions::*;
 
 use crate::builder::{generate_authorisation_code, get_input};
 
@@ -45,6 +58,9 @@ pub struct TokenHandler {
   refresh_token_env: String,
 
   pub subscriptions: Vec<Subscription>,
+
+  // The default client when none has been set
+  http_client: Option<Arc<dyn HttpClient>>,
   //  token_channel_received: RetrievedAuthorisationCode, // Authorisation code
 }
 
@@ -61,7 +77,7 @@ impl TokenHandler {
     &self,
     subs: &[Subscription],
   ) -> Result<bool, TwitchApiError> {
-    twitcheventsub_api::validate_token(&self.user_token).map(|validation| {
+    twitcheventsub_api::validate_token(&self.http_client(), &self.user_token).map(|validation| {
       subs
         .iter()
         .filter(|s| !s.required_scope().is_empty())
@@ -91,9 +107,10 @@ impl TokenHandler {
       self.refresh_token, self.client_id, self.client_secret
     );
 
-    twitcheventsub_api::create_user_and_refresh_token(&post_data).map(
+    twitcheventsub_api::create_user_and_refresh_token(&self.http_client(), &post_data).map(
       |(user_token, refresh_token)| {
-        self.user_token = user_token;
+        let old_token = mem::replace(&mut self.user_token, user_token);
+        twitcheventsub_api::forget_rate_limit(RateLimitKey::from_token(&old_token));
         self.refresh_token = refresh_token;
         Ok(())
       },
@@ -121,22 +138,124 @@ impl TokenHandler {
     }
   }
 
+  /// How much of the Helix rate limit is left for the user token, known
+  /// after the first request.
+  pub fn rate_limit(&self) -> Option<RateLimit> {
+    twitcheventsub_api::rate_limit(RateLimitKey::from_token(&self.user_token))
+  }
+
+  /// Sends this handler's requests with `client`, other handlers keep the
+  /// default one.
+  pub fn set_http_client(&mut self, client: Arc<dyn HttpClient>) {
+    self.http_client = Some(client);
+  }
+
+  /// The client this handler's requests are sent with.
+  pub fn http_client(&self) -> Arc<dyn HttpClient> {
+    self
+      .http_client
+      .clone()
+      .unwrap_or_else(twitcheventsub_api::http_client)
+  }
+
+  /// Collects every page, refreshing the token once if it expires part way.
+  pub fn collect_all_pages<T: DeserializeOwned>(
+    &mut self,
+    pages: PaginatedRequest<T>,
+  ) -> Result<Vec<T>, TwitchApiError> {
+    let mut pages = pages.client(&self.http_client());
+    let mut items = Vec::new();
+    let mut refreshed = false;
+
+    while let Some(item) = pages.next() {
+      match item {
+        Ok(item) => items.push(item),
+        Err(TwitchApiError::TokenRequiresRefreshing(_)) if !refreshed => {
+          self.generate_user_token_from_refresh_token()?;
+          pages.update_token(&self.user_token);
+          refreshed = true;
+        }
+        Err(e) => return Err(e),
+      }
+    }
+
+    Ok(items)
+  }
+
+  /// Runs one request for every [`MAX_QUERY_VALUES`] values, gathering the
+  /// data from each response.
+  fn collect_chunks<T, F>(
+    &mut self,
+    values: &[String],
+    mut request: F,
+  ) -> Result<Vec<T>, TwitchApiError>
+  where
+    T: DeserializeOwned,
+    F: FnMut(&Arc<dyn HttpClient>, &str, &str, &[String]) -> Result<String, TwitchApiError>,
+  {
+    let http_client = self.http_client();
+    let mut data = Vec::new();
+
+    for chunk in values.chunks(MAX_QUERY_VALUES) {
+      let response = request(&http_client, &self.user_token, &self.client_id, chunk);
+      let page: Page<T> = self.regen_tokens_on_fail(response).and_then(|data| {
+        serde_json::from_str(&data).map_err(|e| TwitchApiError::DeserialisationError(e.to_string()))
+      })?;
+      data.extend(page.data);
+    }
+
+    Ok(data)
+  }
+
+  /// Any number of ids and logins, split into as many requests as needed.
   pub fn get_users<I: Into<String>, S: Into<String>>(
     &mut self,
     id: Vec<I>,
     login: Vec<S>,
   ) -> Result<UserDataSet, TwitchApiError> {
-    self
-      .regen_tokens_on_fail(twitcheventsub_api::get_users(
-        &self.user_token,
-        &self.client_id,
-        id,
-        login,
-      ))
-      .and_then(|user_data| match serde_json::from_str(&user_data) {
-        Ok(users) => Ok(users),
-        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
-      })
+    let id = id.into_iter().map(|id| id.into()).collect::<Vec<String>>();
+    let login = login
+      .into_iter()
+      .map(|login| login.into())
+      .collect::<Vec<String>>();
+
+    if id.len() + login.len() <= MAX_QUERY_VALUES {
+      return self
+        .regen_tokens_on_fail(twitcheventsub_api::get_users(
+          &self.http_client(),
+          &self.user_token,
+          &self.client_id,
+          id,
+          login,
+        ))
+        .and_then(|user_data| match serde_json::from_str(&user_data) {
+          Ok(users) => Ok(users),
+          Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+        });
+    }
+
+    let mut data = self.collect_chunks(&id, |http_client, user_token, client_id, id| {
+      twitcheventsub_api::get_users(
+        http_client,
+        user_token,
+        client_id,
+        id.to_vec(),
+        Vec::<String>::new(),
+      )
+    })?;
+    data.extend(
+      self.collect_chunks(&login, |http_client, user_token, client_id, login| {
+        twitcheventsub_api::get_users(
+          http_client,
+          user_token,
+          client_id,
+          Vec::<String>::new(),
+          login.to_vec(),
+        )
+      })?,
+    );
+
+    Ok(UserDataSet { data })
   }
 
   pub fn get_token_user_id(&mut self) -> Result<String, TwitchApiError> {
@@ -156,6 +275,7 @@ impl TokenHandler {
   pub fn get_chatters(&mut self, broadcaster_id: &str) -> Result<GetChatters, TwitchApiError> {
     self
       .regen_tokens_on_fail(twitcheventsub_api::get_chatters(
+        &self.http_client(),
         broadcaster_id,
         &self.client_twitch_id,
         &self.user_token,
@@ -167,6 +287,17 @@ impl TokenHandler {
       })
   }
 
+  /// Every chatter rather than only the first page.
+  pub fn get_chatters_all(&mut self, broadcaster_id: &str) -> Result<Vec<User>, TwitchApiError> {
+    let pages = twitcheventsub_api::get_chatters_all(
+      broadcaster_id,
+      &self.client_twitch_id,
+      &self.user_token,
+      &self.client_id,
+    );
+    self.collect_all_pages(pages)
+  }
+
   pub fn send_chat_message(
     &mut self,
     broadcaster_id: &str,
@@ -182,6 +313,7 @@ impl TokenHandler {
     reply_message_parent_id: Option<String>,
   ) -> Result<String, TwitchApiError> {
     self.regen_tokens_on_fail(twitcheventsub_api::send_chat_message_with_reply(
+      &self.http_client(),
       &self.user_token,
       &self.client_id,
       &self.client_twitch_id,
@@ -191,6 +323,25 @@ impl TokenHandler {
     ))
   }
 
+  /// Whisper from the token user, whisper specific failures come back as
+  /// [`TwitchApiError::Whisper`].
+  pub fn send_whisper(
+    &mut self,
+    to_user_id: &str,
+    message: &str,
+  ) -> Result<String, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::send_whisper(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        &self.client_twitch_id,
+        to_user_id,
+        message,
+      ))
+      .map_err(TwitchApiError::into_whisper_error)
+  }
+
   pub fn send_announcement<P: Into<String>>(
     &mut self,
     broadcaster_id: &str,
@@ -198,6 +349,7 @@ impl TokenHandler {
     colour: Option<P>,
   ) -> Result<String, TwitchApiError> {
     let _ = self.regen_tokens_on_fail(twitcheventsub_api::send_announcement(
+      &self.http_client(),
       &self.user_token,
       &self.client_id,
       &self.client_twitch_id,
@@ -214,6 +366,7 @@ impl TokenHandler {
     to_broadcaster_id: &str,
   ) -> Result<String, TwitchApiError> {
     self.regen_tokens_on_fail(twitcheventsub_api::send_shoutout(
+      &self.http_client(),
       &self.user_token,
       &self.client_id,
       &self.client_twitch_id,
@@ -228,6 +381,7 @@ impl TokenHandler {
     message_id: &str,
   ) -> Result<String, TwitchApiError> {
     self.regen_tokens_on_fail(twitcheventsub_api::delete_message(
+      &self.http_client(),
       &self.user_token,
       &self.client_id,
       &self.client_twitch_id,
@@ -244,6 +398,7 @@ impl TokenHandler {
     reason: &str,
   ) -> Result<String, TwitchApiError> {
     self.regen_tokens_on_fail(twitcheventsub_api::timeout_user(
+      &self.http_client(),
       &self.user_token,
       &self.client_id,
       &self.client_twitch_id,
@@ -261,6 +416,7 @@ impl TokenHandler {
     reason: &str,
   ) -> Result<String, TwitchApiError> {
     self.regen_tokens_on_fail(twitcheventsub_api::timeout_user(
+      &self.http_client(),
       &self.user_token,
       &self.client_id,
       &self.client_twitch_id,
@@ -271,12 +427,79 @@ impl TokenHandler {
     ))
   }
 
+  pub fn warn_chat_user(
+    &mut self,
+    broadcaster_id: &str,
+    user_id: &str,
+    reason: &str,
+  ) -> Result<Warnings, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::warn_chat_user(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        &self.client_twitch_id,
+        broadcaster_id,
+        user_id,
+        reason,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  pub fn get_unban_requests(
+    &mut self,
+    broadcaster_id: &str,
+    status: UnbanRequestStatus,
+  ) -> Result<UnbanRequests, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::get_unban_requests(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+        &self.client_twitch_id,
+        status,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  pub fn resolve_unban_request(
+    &mut self,
+    broadcaster_id: &str,
+    unban_request_id: &str,
+    status: UnbanRequestStatus,
+    resolution_text: Option<&str>,
+  ) -> Result<UnbanRequests, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::resolve_unban_request(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+        &self.client_twitch_id,
+        unban_request_id,
+        status,
+        resolution_text,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
   pub fn get_channel_badges(
     &mut self,
     broadcaster_id: &str,
   ) -> Result<ChannelEmotes, TwitchApiError> {
     self
       .regen_tokens_on_fail(twitcheventsub_api::get_channel_badges(
+        &self.http_client(),
         &self.user_token,
         &self.client_id,
         broadcaster_id,
@@ -290,6 +513,7 @@ impl TokenHandler {
   pub fn get_global_badges(&mut self) -> Result<GlobalEmotes, TwitchApiError> {
     self
       .regen_tokens_on_fail(twitcheventsub_api::get_global_badges(
+        &self.http_client(),
         &self.user_token,
         &self.client_id,
       ))
@@ -302,6 +526,27 @@ impl TokenHandler {
   pub fn get_moderators(&mut self, broadcaster_id: &str) -> Result<Moderators, TwitchApiError> {
     self
       .regen_tokens_on_fail(twitcheventsub_api::get_moderators(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  pub fn get_moderators_all(&mut self, broadcaster_id: &str) -> Result<Vec<User>, TwitchApiError> {
+    let pages =
+      twitcheventsub_api::get_moderators_all(&self.user_token, &self.client_id, broadcaster_id);
+    self.collect_all_pages(pages)
+  }
+
+  pub fn get_vips(&mut self, broadcaster_id: &str) -> Result<Vips, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::get_vips(
+        &self.http_client(),
         &self.user_token,
         &self.client_id,
         broadcaster_id,
@@ -312,12 +557,18 @@ impl TokenHandler {
       })
   }
 
+  pub fn get_vips_all(&mut self, broadcaster_id: &str) -> Result<Vec<User>, TwitchApiError> {
+    let pages = twitcheventsub_api::get_vips_all(&self.user_token, &self.client_id, broadcaster_id);
+    self.collect_all_pages(pages)
+  }
+
   pub fn get_custom_rewards(
     &mut self,
     broadcaster_id: &str,
   ) -> Result<GetCustomRewards, TwitchApiError> {
     self
       .regen_tokens_on_fail(twitcheventsub_api::get_custom_rewards(
+        &self.http_client(),
         &self.user_token,
         &self.client_id,
         broadcaster_id,
@@ -336,6 +587,7 @@ impl TokenHandler {
   ) -> Result<CreatedCustomRewardResponse, TwitchApiError> {
     self
       .regen_tokens_on_fail(twitcheventsub_api::update_custom_rewards(
+        &self.http_client(),
         &self.user_token,
         &self.client_id,
         broadcaster_id,
@@ -355,6 +607,7 @@ impl TokenHandler {
   ) -> Result<CreatedCustomRewardResponse, TwitchApiError> {
     self
       .regen_tokens_on_fail(twitcheventsub_api::create_custom_reward(
+        &self.http_client(),
         &self.user_token,
         &self.client_id,
         broadcaster_id,
@@ -373,6 +626,7 @@ impl TokenHandler {
   ) -> Result<String, TwitchApiError> {
     self
       .regen_tokens_on_fail(twitcheventsub_api::delete_custom_reward(
+        &self.http_client(),
         &self.user_token,
         &self.client_id,
         broadcaster_id,
@@ -387,6 +641,7 @@ impl TokenHandler {
   pub fn get_clips(&mut self, broadcaster_id: &str) -> Result<Clips, TwitchApiError> {
     self
       .regen_tokens_on_fail(twitcheventsub_api::get_clips(
+        &self.http_client(),
         &self.user_token,
         &self.client_id,
         broadcaster_id,
@@ -397,9 +652,34 @@ impl TokenHandler {
       })
   }
 
+  pub fn get_clips_by_id<S: Into<String>>(
+    &mut self,
+    clip_ids: Vec<S>,
+  ) -> Result<Clips, TwitchApiError> {
+    let clip_ids = clip_ids
+      .into_iter()
+      .map(|id| id.into())
+      .collect::<Vec<String>>();
+    let data = self.collect_chunks(&clip_ids, |http_client, user_token, client_id, clip_ids| {
+      twitcheventsub_api::get_clips_by_id(http_client, user_token, client_id, clip_ids)
+    })?;
+
+    Ok(Clips { data })
+  }
+
+  pub fn get_clips_all(
+    &mut self,
+    broadcaster_id: &str,
+  ) -> Result<Vec<ClipDetails>, TwitchApiError> {
+    let pages =
+      twitcheventsub_api::get_clips_all(&self.user_token, &self.client_id, broadcaster_id);
+    self.collect_all_pages(pages)
+  }
+
   pub fn get_ad_schedule(&mut self, broadcaster_id: &str) -> Result<AdSchedule, TwitchApiError> {
     self
       .regen_tokens_on_fail(twitcheventsub_api::get_ad_schedule(
+        &self.http_client(),
         &self.user_token,
         &self.client_id,
         broadcaster_id,
@@ -410,12 +690,127 @@ impl TokenHandler {
       })
   }
 
+  /// Needs [`Subscription::PermissionStartCommercial`] and the broadcaster
+  /// to be live.
+  pub fn start_commercial(
+    &mut self,
+    broadcaster_id: &str,
+    length_secs: u32,
+  ) -> Result<Commercial, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::start_commercial(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+        length_secs,
+      ))
+      .and_then(|data| match serde_json::from_str::<Commercials>(&data) {
+        Ok(mut data) if !data.data.is_empty() => Ok(data.data.remove(0)),
+        Ok(_) => Err(TwitchApiError::DeserialisationError(String::from(
+          "Start commercial response had no data.",
+        ))),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  /// Needs [`Subscription::PermissionManageAds`].
+  pub fn snooze_next_ad(&mut self, broadcaster_id: &str) -> Result<SnoozedAd, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::snooze_next_ad(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+      ))
+      .and_then(|data| match serde_json::from_str::<SnoozedAds>(&data) {
+        Ok(mut data) if !data.data.is_empty() => Ok(data.data.remove(0)),
+        Ok(_) => Err(TwitchApiError::DeserialisationError(String::from(
+          "Snooze next ad response had no data.",
+        ))),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  pub fn get_streams(&mut self, user_id: &str) -> Result<Streams, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::get_streams(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        user_id,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  /// The live streams of any number of users, split into as many requests
+  /// as needed.
+  pub fn get_streams_for_users<I: Into<String>, S: Into<String>>(
+    &mut self,
+    user_ids: Vec<I>,
+    user_logins: Vec<S>,
+  ) -> Result<Streams, TwitchApiError> {
+    let user_ids = user_ids
+      .into_iter()
+      .map(|id| id.into())
+      .collect::<Vec<String>>();
+    let user_logins = user_logins
+      .into_iter()
+      .map(|login| login.into())
+      .collect::<Vec<String>>();
+
+    let mut data =
+      self.collect_chunks(&user_ids, |http_client, user_token, client_id, user_ids| {
+        twitcheventsub_api::get_streams_for_users(
+          http_client,
+          user_token,
+          client_id,
+          user_ids,
+          &[] as &[String],
+        )
+      })?;
+    data.extend(self.collect_chunks(
+      &user_logins,
+      |http_client, user_token, client_id, user_logins| {
+        twitcheventsub_api::get_streams_for_users(
+          http_client,
+          user_token,
+          client_id,
+          &[] as &[String],
+          user_logins,
+        )
+      },
+    )?);
+
+    Ok(Streams {
+      data,
+      pagination: None,
+    })
+  }
+
+  /// None when the broadcaster is offline.
+  pub fn get_stream(&mut self, broadcaster_id: &str) -> Result<Option<StreamData>, TwitchApiError> {
+    self
+      .get_streams(broadcaster_id)
+      .map(|streams| streams.data.into_iter().find(|stream| stream.is_live()))
+  }
+
+  pub fn is_live(&mut self, broadcaster_id: &str) -> Result<bool, TwitchApiError> {
+    self
+      .get_stream(broadcaster_id)
+      .map(|stream| stream.is_some())
+  }
+
   pub fn get_hype_train_status(
     &mut self,
     broadcaster_id: &str,
   ) -> Result<HypeTrainStatus, TwitchApiError> {
     self
       .regen_tokens_on_fail(twitcheventsub_api::get_hype_train_status(
+        &self.http_client(),
         &self.user_token,
         &self.client_id,
         broadcaster_id,
@@ -426,6 +821,347 @@ impl TokenHandler {
       })
   }
 
+  pub fn get_charity_campaign(
+    &mut self,
+    broadcaster_id: &str,
+  ) -> Result<CharityCampaigns, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::get_charity_campaign(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  pub fn get_charity_campaign_donations(
+    &mut self,
+    broadcaster_id: &str,
+  ) -> Result<CharityDonations, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::get_charity_campaign_donations(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  /// Starts a poll in the broadcaster's channel, the token must belong to
+  /// the broadcaster.
+  pub fn create_poll(
+    &mut self,
+    broadcaster_id: &str,
+    poll: PollBuilder,
+  ) -> Result<Polls, TwitchApiError> {
+    let poll = poll.build(broadcaster_id)?;
+
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::create_poll(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        &poll,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  pub fn get_polls<S: AsRef<str>>(
+    &mut self,
+    broadcaster_id: &str,
+    poll_ids: &[S],
+  ) -> Result<Polls, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::get_polls(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+        poll_ids,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  /// Ends the poll early, archiving it also hides the results from viewers.
+  pub fn end_poll(
+    &mut self,
+    broadcaster_id: &str,
+    poll_id: &str,
+    archive: bool,
+  ) -> Result<Polls, TwitchApiError> {
+    let status = if archive {
+      PollStatus::Archived
+    } else {
+      PollStatus::Terminated
+    };
+
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::end_poll(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+        poll_id,
+        status,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  /// Starts a prediction in the broadcaster's channel, the token must
+  /// belong to the broadcaster.
+  pub fn create_prediction(
+    &mut self,
+    broadcaster_id: &str,
+    prediction: PredictionBuilder,
+  ) -> Result<Predictions, TwitchApiError> {
+    let prediction = prediction.build(broadcaster_id)?;
+
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::create_prediction(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        &prediction,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  pub fn get_predictions<S: AsRef<str>>(
+    &mut self,
+    broadcaster_id: &str,
+    prediction_ids: &[S],
+  ) -> Result<Predictions, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::get_predictions(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+        prediction_ids,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  pub fn end_prediction(
+    &mut self,
+    broadcaster_id: &str,
+    prediction_id: &str,
+    status: PredictionStatus,
+    winning_outcome_id: Option<&str>,
+  ) -> Result<Predictions, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::end_prediction(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+        prediction_id,
+        status,
+        winning_outcome_id,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  /// Stops new predictions, resolve or cancel it later.
+  pub fn lock_prediction(
+    &mut self,
+    broadcaster_id: &str,
+    prediction_id: &str,
+  ) -> Result<Predictions, TwitchApiError> {
+    self.end_prediction(
+      broadcaster_id,
+      prediction_id,
+      PredictionStatus::Locked,
+      None,
+    )
+  }
+
+  /// Pays out the channel points to everyone that picked `winning_outcome_id`.
+  pub fn resolve_prediction(
+    &mut self,
+    broadcaster_id: &str,
+    prediction_id: &str,
+    winning_outcome_id: &str,
+  ) -> Result<Predictions, TwitchApiError> {
+    self.end_prediction(
+      broadcaster_id,
+      prediction_id,
+      PredictionStatus::Resolved,
+      Some(winning_outcome_id),
+    )
+  }
+
+  /// Refunds every channel point spent on the prediction.
+  pub fn cancel_prediction(
+    &mut self,
+    broadcaster_id: &str,
+    prediction_id: &str,
+  ) -> Result<Predictions, TwitchApiError> {
+    self.end_prediction(
+      broadcaster_id,
+      prediction_id,
+      PredictionStatus::Cancelled,
+      None,
+    )
+  }
+
+  /// Any number of channels, split into as many requests as needed.
+  pub fn get_channel_information<S: Into<String>>(
+    &mut self,
+    broadcaster_ids: Vec<S>,
+  ) -> Result<Channels, TwitchApiError> {
+    let broadcaster_ids = broadcaster_ids
+      .into_iter()
+      .map(|id| id.into())
+      .collect::<Vec<String>>();
+    let data = self.collect_chunks(
+      &broadcaster_ids,
+      |http_client, user_token, client_id, broadcaster_ids| {
+        twitcheventsub_api::get_channel_information(
+          http_client,
+          user_token,
+          client_id,
+          broadcaster_ids,
+        )
+      },
+    )?;
+
+    Ok(Channels { data })
+  }
+
+  pub fn get_channel(
+    &mut self,
+    broadcaster_id: &str,
+  ) -> Result<ChannelInformation, TwitchApiError> {
+    self
+      .get_channel_information(vec![broadcaster_id])
+      .and_then(|mut channels| {
+        if channels.data.is_empty() {
+          Err(TwitchApiError::InputError(format!(
+            "No channel found for broadcaster {}.",
+            broadcaster_id
+          )))
+        } else {
+          Ok(channels.data.remove(0))
+        }
+      })
+  }
+
+  /// Needs [`Subscription::PermissionManageBroadcast`] from the broadcaster.
+  pub fn modify_channel_information(
+    &mut self,
+    broadcaster_id: &str,
+    channel_information: &ModifyChannelInformation,
+  ) -> Result<(), TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::modify_channel_information(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        broadcaster_id,
+        channel_information,
+      ))
+      .map(|_| ())
+  }
+
+  pub fn set_stream_title<S: Into<String>>(
+    &mut self,
+    broadcaster_id: &str,
+    title: S,
+  ) -> Result<(), TwitchApiError> {
+    self.modify_channel_information(
+      broadcaster_id,
+      &ModifyChannelInformation {
+        title: Some(title.into()),
+        ..Default::default()
+      },
+    )
+  }
+
+  /// An empty `game_id` clears the category.
+  pub fn set_stream_category<S: Into<String>>(
+    &mut self,
+    broadcaster_id: &str,
+    game_id: S,
+  ) -> Result<(), TwitchApiError> {
+    self.modify_channel_information(
+      broadcaster_id,
+      &ModifyChannelInformation {
+        game_id: Some(game_id.into()),
+        ..Default::default()
+      },
+    )
+  }
+
+  pub fn search_categories(&mut self, query: &str) -> Result<Categories, TwitchApiError> {
+    self
+      .regen_tokens_on_fail(twitcheventsub_api::search_categories(
+        &self.http_client(),
+        &self.user_token,
+        &self.client_id,
+        query,
+        None,
+      ))
+      .and_then(|data| match serde_json::from_str(&data) {
+        Ok(data) => Ok(data),
+        Err(e) => Err(TwitchApiError::DeserialisationError(e.to_string())),
+      })
+  }
+
+  /// Any number of ids and exact names, split into as many requests as
+  /// needed.
+  pub fn get_games<I: Into<String>, S: Into<String>>(
+    &mut self,
+    ids: Vec<I>,
+    names: Vec<S>,
+  ) -> Result<Games, TwitchApiError> {
+    let ids = ids.into_iter().map(|id| id.into()).collect::<Vec<String>>();
+    let names = names
+      .into_iter()
+      .map(|name| name.into())
+      .collect::<Vec<String>>();
+
+    let mut data = self.collect_chunks(&ids, |http_client, user_token, client_id, ids| {
+      twitcheventsub_api::get_games(http_client, user_token, client_id, ids, &[] as &[String])
+    })?;
+    data.extend(
+      self.collect_chunks(&names, |http_client, user_token, client_id, names| {
+        twitcheventsub_api::get_games(http_client, user_token, client_id, &[] as &[String], names)
+      })?,
+    );
+
+    Ok(Games {
+      data,
+      pagination: None,
+    })
+  }
+
   pub fn wait_for_resposne_from_generate_user_and_refreshed_tokens_threaded() {}
 
   // After recieving the authorisation code from the Receiver
@@ -482,6 +1218,7 @@ impl TokenHandler {
     scopes: &[Subscription],
   ) -> Option<TokenBuilderError> {
     let (user_token, refresh_token) = get_user_and_refresh_token_from_authorisation_code(
+      &self.http_client(),
       &self.client_id,
       &self.client_secret,
       &authorisation_code,
@@ -490,10 +1227,11 @@ impl TokenHandler {
     .map_err(|e| TokenBuilderError::TwitchApiError(e))
     .ok()?;
 
-    self.user_token = user_token;
+    let old_token = mem::replace(&mut self.user_token, user_token);
+    twitcheventsub_api::forget_rate_limit(RateLimitKey::from_token(&old_token));
     self.refresh_token = refresh_token;
 
-    if let Ok(valid) = validate_token(&self.user_token) {
+    if let Ok(valid) = validate_token(&self.http_client(), &self.user_token) {
       if valid.status.is_some() {
         // it is not valid
         return Some(TokenBuilderError::InvalidUserToken)
@@ -0,0 +1,106 @@
+use twitcheventsub_api::TwitchApiError;
+use twitcheventsub_structs::prelude::{CreatePoll, CreatePollChoice};
+
+pub const MAX_POLL_TITLE_LENGTH: usize = 60;
+pub const MAX_POLL_CHOICE_TITLE_LENGTH: usize = 25;
+pub const MIN_POLL_CHOICES: usize = 2;
+pub const MAX_POLL_CHOICES: usize = 5;
+/// Seconds
+pub const MIN_POLL_DURATION: u32 = 15;
+/// Seconds
+pub const MAX_POLL_DURATION: u32 = 1800;
+pub const MAX_POLL_CHANNEL_POINTS_PER_VOTE: u32 = 1_000_000;
+
+/// A poll to start with [`crate::TokenHandler::create_poll`], checked against
+/// twitch's limits before it is sent.
+#[derive(Clone, Debug)]
+pub struct PollBuilder {
+  title: String,
+  choices: Vec<String>,
+  duration_secs: u32,
+  channel_points_per_vote: Option<u32>,
+}
+
+impl PollBuilder {
+  /// Runs for 60 seconds unless a duration is set.
+  pub fn new<S: Into<String>>(title: S) -> PollBuilder {
+    PollBuilder {
+      title: title.into(),
+      choices: Vec::new(),
+      duration_secs: 60,
+      channel_points_per_vote: None,
+    }
+  }
+
+  pub fn choice<S: Into<String>>(mut self, title: S) -> PollBuilder {
+    self.choices.push(title.into());
+    self
+  }
+
+  pub fn duration_secs(mut self, duration_secs: u32) -> PollBuilder {
+    self.duration_secs = duration_secs;
+    self
+  }
+
+  /// Lets viewers spend channel points for extra votes.
+  pub fn channel_points_voting(mut self, channel_points_per_vote: u32) -> PollBuilder {
+    self.channel_points_per_vote = Some(channel_points_per_vote);
+    self
+  }
+
+  pub fn build(self, broadcaster_id: &str) -> Result<CreatePoll, TwitchApiError> {
+    if self.title.is_empty() || self.title.chars().count() > MAX_POLL_TITLE_LENGTH {
+      return Err(TwitchApiError::InputError(format!(
+        "Poll title must be between 1 and {} characters.",
+        MAX_POLL_TITLE_LENGTH
+      )));
+    }
+
+    if self.choices.len() < MIN_POLL_CHOICES || self.choices.len() > MAX_POLL_CHOICES {
+      return Err(TwitchApiError::InputError(format!(
+        "Polls need between {} and {} choices.",
+        MIN_POLL_CHOICES, MAX_POLL_CHOICES
+      )));
+    }
+
+    if self
+      .choices
+      .iter()
+      .any(|choice| choice.is_empty() || choice.chars().count() > MAX_POLL_CHOICE_TITLE_LENGTH)
+    {
+      return Err(TwitchApiError::InputError(format!(
+        "Poll choices must be between 1 and {} characters.",
+        MAX_POLL_CHOICE_TITLE_LENGTH
+      )));
+    }
+
+    if self.duration_secs < MIN_POLL_DURATION || self.duration_secs > MAX_POLL_DURATION {
+      return Err(TwitchApiError::InputError(format!(
+        "Poll duration must be between {} and {} seconds.",
+        MIN_POLL_DURATION, MAX_POLL_DURATION
+      )));
+    }
+
+    if let Some(per_vote) = self.channel_points_per_vote &&
+      (per_vote == 0 || per_vote > MAX_POLL_CHANNEL_POINTS_PER_VOTE)
+    {
+      return Err(TwitchApiError::InputError(format!(
+        "Channel points per vote must be between 1 and {}.",
+        MAX_POLL_CHANNEL_POINTS_PER_VOTE
+      )));
+    }
+
+    Ok(CreatePoll {
+      broadcaster_id: broadcaster_id.to_owned(),
+      title: self.title,
+      choices: self
+        .choices
+        .into_iter()
+        .map(|title| CreatePollChoice { title })
+        .collect(),
+      duration: self.duration_secs,
+      channel_points_voting_enabled: self.channel_points_per_vote.map(|_| true),
+      channel_points_per_vote: self.channel_points_per_vote,
+    })
+  }
+}
@@ -0,0 +1,89 @@
+use twitcheventsub_api::TwitchApiError;
+use twitcheventsub_structs::prelude::{CreatePrediction, CreatePredictionOutcome};
+
+pub const MAX_PREDICTION_TITLE_LENGTH: usize = 45;
+pub const MAX_PREDICTION_OUTCOME_TITLE_LENGTH: usize = 25;
+pub const MIN_PREDICTION_OUTCOMES: usize = 2;
+pub const MAX_PREDICTION_OUTCOMES: usize = 10;
+/// Seconds
+pub const MIN_PREDICTION_WINDOW: u32 = 30;
+/// Seconds
+pub const MAX_PREDICTION_WINDOW: u32 = 1800;
+
+/// A prediction to start with [`crate::TokenHandler::create_prediction`],
+/// checked against twitch's limits before it is sent.
+#[derive(Clone, Debug)]
+pub struct PredictionBuilder {
+  title: String,
+  outcomes: Vec<String>,
+  prediction_window_secs: u32,
+}
+
+impl PredictionBuilder {
+  /// Takes predictions for 120 seconds unless a window is set.
+  pub fn new<S: Into<String>>(title: S) -> PredictionBuilder {
+    PredictionBuilder {
+      title: title.into(),
+      outcomes: Vec::new(),
+      prediction_window_secs: 120,
+    }
+  }
+
+  pub fn outcome<S: Into<String>>(mut self, title: S) -> PredictionBuilder {
+    self.outcomes.push(title.into());
+    self
+  }
+
+  /// How long viewers can make predictions before it locks.
+  pub fn prediction_window_secs(mut self, prediction_window_secs: u32) -> PredictionBuilder {
+    self.prediction_window_secs = prediction_window_secs;
+    self
+  }
+
+  pub fn build(self, broadcaster_id: &str) -> Result<CreatePrediction, TwitchApiError> {
+    if self.title.is_empty() || self.title.chars().count() > MAX_PREDICTION_TITLE_LENGTH {
+      return Err(TwitchApiError::InputError(format!(
+        "Prediction title must be between 1 and {} characters.",
+        MAX_PREDICTION_TITLE_LENGTH
+      )));
+    }
+
+    if self.outcomes.len() < MIN_PREDICTION_OUTCOMES ||
+      self.outcomes.len() > MAX_PREDICTION_OUTCOMES
+    {
+      return Err(TwitchApiError::InputError(format!(
+        "Predictions need between {} and {} outcomes.",
+        MIN_PREDICTION_OUTCOMES, MAX_PREDICTION_OUTCOMES
+      )));
+    }
+
+    if self.outcomes.iter().any(|outcome| {
+      outcome.is_empty() || outcome.chars().count() > MAX_PREDICTION_OUTCOME_TITLE_LENGTH
+    }) {
+      return Err(TwitchApiError::InputError(format!(
+        "Prediction outcomes must be between 1 and {} characters.",
+        MAX_PREDICTION_OUTCOME_TITLE_LENGTH
+      )));
+    }
+
+    if self.prediction_window_secs < MIN_PREDICTION_WINDOW ||
+      self.prediction_window_secs > MAX_PREDICTION_WINDOW
+    {
+      return Err(TwitchApiError::InputError(format!(
+        "Prediction window must be between {} and {} seconds.",
+        MIN_PREDICTION_WINDOW, MAX_PREDICTION_WINDOW
+      )));
+    }
+
+    Ok(CreatePrediction {
+      broadcaster_id: broadcaster_id.to_owned(),
+      title: self.title,
+      outcomes: self
+        .outcomes
+        .into_iter()
+        .map(|title| CreatePredictionOutcome { title })
+        .collect(),
+      prediction_window: self.prediction_window_secs,
+    })
+  }
+}
@@ -0,0 +1,438 @@
+use std::sync::Arc;
+
+use twitcheventsub_api::{
+  CHANNELS_URL, GET_CHATTERS_URL, GET_STREAMS_URL, GET_USERS_URL, HttpMethod, HttpResponse,
+  MockHttpClient, POLLS_URL, PREDICTIONS_URL, SEARCH_CATEGORIES_URL, SEND_MESSAGE_URL,
+  SEND_WHISPER_URL, TWITCH_TOKEN_URL, TwitchApiError, WhisperError,
+};
+use twitcheventsub_tokens::TokenHandler;
+
+const USER: &str = r#"{
+  "id": "141981764",
+  "login": "twitchdev",
+  "display_name": "TwitchDev",
+  "type": "",
+  "broadcaster_type": "partner",
+  "description": "Supporting third-party developers building Twitch integrations",
+  "profile_image_url": "https://static-cdn.jtvnw.net/profile.png",
+  "offline_image_url": "https://static-cdn.jtvnw.net/offline.png",
+  "view_count": 5980557,
+  "created_at": "2016-12-14T20:32:28Z"
+}"#;
+
+// Every test has its own token so the rate limits they track don't mix
+fn tokens(user_token: &str) -> (TokenHandler, Arc<MockHttpClient>) {
+  let client = Arc::new(MockHttpClient::new());
+
+  let mut tokens = TokenHandler::new();
+  tokens.user_token = user_token.to_owned();
+  tokens.refresh_token = format!("{}_refresh", user_token);
+  tokens.client_id = "client".to_owned();
+  tokens.client_secret = "secret".to_owned();
+  tokens.client_twitch_id = "12826".to_owned();
+  tokens.set_http_client(client.clone());
+
+  (tokens, client)
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+  headers
+    .iter()
+    .find(|(key, _)| key.eq_ignore_ascii_case(name))
+    .map(|(_, value)| value.as_str())
+}
+
+#[test]
+fn get_users_sends_ids_logins_and_auth() {
+  let (mut tokens, client) = tokens("get_users");
+  client.respond(GET_USERS_URL, 200, format!(r#"{{"data":[{}]}}"#, USER));
+
+  let users = tokens
+    .get_users(vec!["141981764"], vec!["twitch dev"])
+    .unwrap();
+  assert_eq!(users.data.len(), 1);
+  assert_eq!(users.data[0].name, "TwitchDev");
+
+  let requests = client.requests();
+  assert_eq!(requests.len(), 1);
+  assert_eq!(requests[0].method, HttpMethod::Get);
+  assert_eq!(
+    requests[0].url,
+    format!("{}?id=141981764&login=twitch%20dev", GET_USERS_URL)
+  );
+  assert_eq!(
+    header(&requests[0].headers, "Authorization"),
+    Some("Bearer get_users")
+  );
+  assert_eq!(header(&requests[0].headers, "Client-Id"), Some("client"));
+}
+
+#[test]
+fn get_users_splits_into_requests_of_100() {
+  let (mut tokens, client) = tokens("get_users_chunked");
+  client.respond(GET_USERS_URL, 200, format!(r#"{{"data":[{}]}}"#, USER));
+
+  let ids = (0..150).map(|id| id.to_string()).collect::<Vec<_>>();
+  let users = tokens.get_users(ids, Vec::<String>::new()).unwrap();
+  assert_eq!(users.data.len(), 2);
+
+  let requests = client.requests();
+  assert_eq!(requests.len(), 2);
+  assert_eq!(requests[0].url.matches("id=").count(), 100);
+  assert_eq!(requests[1].url.matches("id=").count(), 50);
+}
+
+#[test]
+fn get_chatters_all_follows_the_cursor() {
+  let (mut tokens, client) = tokens("get_chatters_all");
+  let first_page = format!(
+    "{}?broadcaster_id=141981764&moderator_id=12826&first=1000",
+    GET_CHATTERS_URL
+  );
+  client.respond(
+    &first_page,
+    200,
+    r#"{"data":[{"user_id":"1","user_login":"one","user_name":"One"}],"pagination":{"cursor":"abc"},"total":2}"#,
+  );
+  client.respond(
+    format!("{}&after=abc", first_page),
+    200,
+    r#"{"data":[{"user_id":"2","user_login":"two","user_name":"Two"}],"pagination":{},"total":2}"#,
+  );
+
+  let chatters = tokens.get_chatters_all("141981764").unwrap();
+  assert_eq!(
+    chatters
+      .iter()
+      .map(|chatter| chatter.login.as_str())
+      .collect::<Vec<_>>(),
+    vec!["one", "two"]
+  );
+  assert_eq!(client.requests().len(), 2);
+}
+
+#[test]
+fn send_chat_message_posts_json() {
+  let (mut tokens, client) = tokens("send_chat_message");
+  client.respond(
+    SEND_MESSAGE_URL,
+    200,
+    r#"{"data":[{"message_id":"abc-123","is_sent":true}]}"#,
+  );
+
+  tokens.send_chat_message("141981764", "Hello!").unwrap();
+
+  let requests = client.requests();
+  assert_eq!(requests.len(), 1);
+  assert_eq!(requests[0].method, HttpMethod::Post);
+  assert_eq!(
+    header(&requests[0].headers, "Content-Type"),
+    Some("application/json")
+  );
+  assert!(requests[0].body.as_ref().unwrap().contains("Hello!"));
+}
+
+#[test]
+fn post_server_errors_are_not_sent_again() {
+  let (mut tokens, client) = tokens("post_server_error");
+  client.respond(
+    SEND_MESSAGE_URL,
+    503,
+    r#"{"error":"Service Unavailable","status":503,"message":""}"#,
+  );
+
+  let result = tokens.send_chat_message("141981764", "Hello!");
+  assert!(matches!(
+    result,
+    Err(TwitchApiError::Http { status: 503, .. })
+  ));
+  assert_eq!(client.requests().len(), 1);
+}
+
+#[test]
+fn missing_scope_names_the_subscription() {
+  let (mut tokens, client) = tokens("missing_scope");
+  client.respond(
+    GET_CHATTERS_URL,
+    401,
+    r#"{"error":"Unauthorized","status":401,"message":"Missing scope: moderator:read:chatters"}"#,
+  );
+
+  let result = tokens.get_chatters("141981764");
+  assert!(matches!(
+    result,
+    Err(TwitchApiError::TokenMissingSubscription(_))
+  ));
+}
+
+#[test]
+fn whisper_without_phone_number_is_a_whisper_error() {
+  let (mut tokens, client) = tokens("whisper_phone_number");
+  client.respond(
+    SEND_WHISPER_URL,
+    401,
+    r#"{"error":"Unauthorized","status":401,"message":"the sender does not have a verified phone number"}"#,
+  );
+
+  let result = tokens.send_whisper("141981764", "Hello!");
+  assert_eq!(
+    result,
+    Err(TwitchApiError::Whisper(
+      WhisperError::PhoneNumberNotVerified
+    ))
+  );
+  // No token refresh was attempted
+  assert_eq!(client.requests().len(), 1);
+}
+
+#[test]
+fn whisper_length_counts_characters() {
+  let (mut tokens, client) = tokens("whisper_length");
+  client.respond(SEND_WHISPER_URL, 204, "");
+
+  // 4 bytes each, so well over 10000 bytes
+  assert!(
+    tokens
+      .send_whisper("141981764", &"🦀".repeat(10000))
+      .is_ok()
+  );
+  assert!(matches!(
+    tokens.send_whisper("141981764", &"🦀".repeat(10001)),
+    Err(TwitchApiError::InputError(_))
+  ));
+  assert_eq!(client.requests().len(), 1);
+}
+
+#[test]
+fn long_whisper_to_new_recipient_is_a_whisper_error() {
+  let (mut tokens, client) = tokens("whisper_new_recipient");
+  client.respond(
+    SEND_WHISPER_URL,
+    400,
+    r#"{"error":"Bad Request","status":400,"message":"The message may not exceed 500 characters when sending to a new recipient"}"#,
+  );
+
+  let result = tokens.send_whisper("141981764", &"a".repeat(501));
+  assert!(matches!(
+    result,
+    Err(TwitchApiError::Whisper(WhisperError::MessageTooLong(_)))
+  ));
+}
+
+#[test]
+fn expired_token_is_refreshed_and_the_request_sent_again() {
+  let (mut tokens, client) = tokens("expired");
+  client.respond(
+    GET_USERS_URL,
+    401,
+    r#"{"error":"Unauthorized","status":401,"message":"Invalid OAuth token"}"#,
+  );
+  client.respond(
+    TWITCH_TOKEN_URL,
+    200,
+    r#"{"access_token":"refreshed","refresh_token":"refreshed_refresh","expires_in":14124,"scope":[],"token_type":"bearer"}"#,
+  );
+
+  // The mock keeps answering 401, so the second attempt still fails
+  let _ = tokens.get_users(vec!["141981764"], Vec::<String>::new());
+  assert_eq!(tokens.user_token, "refreshed");
+  assert_eq!(tokens.refresh_token, "refreshed_refresh");
+
+  let requests = client.requests();
+  assert_eq!(requests.len(), 3);
+  assert_eq!(requests[1].url, TWITCH_TOKEN_URL);
+  assert_eq!(requests[1].method, HttpMethod::Post);
+  assert_eq!(
+    header(&requests[2].headers, "Authorization"),
+    Some("Bearer refreshed")
+  );
+}
+
+#[test]
+fn rate_limit_is_read_from_the_response() {
+  let (mut tokens, client) = tokens("rate_limit");
+  client.respond_with(
+    SEARCH_CATEGORIES_URL,
+    HttpResponse {
+      status: 200,
+      headers: vec![
+        ("Ratelimit-Limit".to_owned(), "800".to_owned()),
+        ("Ratelimit-Remaining".to_owned(), "799".to_owned()),
+        ("Ratelimit-Reset".to_owned(), "4102444800".to_owned()),
+      ],
+      body: br#"{"data":[{"id":"33214","name":"Fortnite","box_art_url":"https://static-cdn.jtvnw.net/box.jpg"}],"pagination":{}}"#.to_vec(),
+    },
+  );
+
+  let categories = tokens.search_categories("fort").unwrap();
+  assert_eq!(categories.data[0].name, "Fortnite");
+
+  let rate_limit = tokens.rate_limit().unwrap();
+  assert_eq!(rate_limit.limit, 800);
+  assert_eq!(rate_limit.remaining, 799);
+}
+
+#[test]
+fn get_polls_reads_the_broadcaster() {
+  let (mut tokens, client) = tokens("get_polls");
+  client.respond(
+    POLLS_URL,
+    200,
+    r#"{
+      "data": [{
+        "id": "ed961efd-8a3f-4cf5-a9d0-e616c590cd2a",
+        "broadcaster_id": "55696719",
+        "broadcaster_name": "TwitchDev",
+        "broadcaster_login": "twitchdev",
+        "title": "Heads or Tails?",
+        "choices": [
+          {"id": "4c123012-1351-4f33-84b7-43856e7a0f47", "title": "Heads", "votes": 0, "channel_points_votes": 0, "bits_votes": 0},
+          {"id": "279087e3-54a7-467e-bcd0-c1393fcea4f0", "title": "Tails", "votes": 0, "channel_points_votes": 0, "bits_votes": 0}
+        ],
+        "bits_voting_enabled": false,
+        "bits_per_vote": 0,
+        "channel_points_voting_enabled": false,
+        "channel_points_per_vote": 0,
+        "status": "ACTIVE",
+        "duration": 1800,
+        "started_at": "2021-03-19T06:08:33.871278372Z"
+      }],
+      "pagination": {}
+    }"#,
+  );
+
+  let polls = tokens
+    .get_polls("55696719", &["ed961efd-8a3f-4cf5-a9d0-e616c590cd2a"])
+    .unwrap();
+  assert_eq!(polls.data[0].broadcaster_id, "55696719");
+  assert_eq!(polls.data[0].broadcaster_login, "twitchdev");
+  assert_eq!(polls.data[0].broadcaster_name, "TwitchDev");
+  assert_eq!(polls.data[0].choices.len(), 2);
+  assert_eq!(
+    client.requests()[0].url,
+    format!(
+      "{}?broadcaster_id=55696719&id=ed961efd-8a3f-4cf5-a9d0-e616c590cd2a",
+      POLLS_URL
+    )
+  );
+}
+
+#[test]
+fn get_predictions_reads_the_broadcaster() {
+  let (mut tokens, client) = tokens("get_predictions");
+  client.respond(
+    PREDICTIONS_URL,
+    200,
+    r#"{
+      "data": [{
+        "id": "d6676d5c-c86e-44d2-bfc4-100fb48f0656",
+        "broadcaster_id": "55696719",
+        "broadcaster_name": "TwitchDev",
+        "broadcaster_login": "twitchdev",
+        "title": "Will there be any leaks today?",
+        "winning_outcome_id": null,
+        "outcomes": [
+          {"id": "021e9234-5893-49b4-982e-cfe9a0aaddd9", "title": "Yes", "users": 0, "channel_points": 0, "top_predictors": null, "color": "BLUE"},
+          {"id": "ded84c26-13cb-4b48-8cb5-5bae3ec3a66e", "title": "No", "users": 0, "channel_points": 0, "top_predictors": null, "color": "PINK"}
+        ],
+        "prediction_window": 600,
+        "status": "ACTIVE",
+        "created_at": "2021-04-28T16:03:06.320848689Z",
+        "ended_at": null,
+        "locked_at": null
+      }],
+      "pagination": {}
+    }"#,
+  );
+
+  let predictions = tokens.get_predictions("55696719", &[] as &[&str]).unwrap();
+  assert_eq!(predictions.data[0].broadcaster_id, "55696719");
+  assert_eq!(predictions.data[0].broadcaster_login, "twitchdev");
+  assert_eq!(predictions.data[0].broadcaster_name, "TwitchDev");
+  assert_eq!(predictions.data[0].outcomes.len(), 2);
+}
+
+#[test]
+fn get_channel_reads_the_broadcaster() {
+  let (mut tokens, client) = tokens("get_channel");
+  client.respond(
+    CHANNELS_URL,
+    200,
+    r#"{
+      "data": [{
+        "broadcaster_id": "141981764",
+        "broadcaster_login": "twitchdev",
+        "broadcaster_name": "TwitchDev",
+        "broadcaster_language": "en",
+        "game_id": "509670",
+        "game_name": "Science & Technology",
+        "title": "TwitchDev Monthly Update // May 6, 2021",
+        "delay": 0,
+        "tags": ["DevsInTheKnow"],
+        "content_classification_labels": ["Gambling", "DrugsIntoxication", "MatureGame"],
+        "is_branded_content": false
+      }]
+    }"#,
+  );
+
+  let channel = tokens.get_channel("141981764").unwrap();
+  assert_eq!(channel.broadcaster_id, "141981764");
+  assert_eq!(channel.broadcaster_login, "twitchdev");
+  assert_eq!(channel.broadcaster_name, "TwitchDev");
+  assert_eq!(channel.broadcaster_language, "en");
+  assert_eq!(
+    client.requests()[0].url,
+    format!("{}?broadcaster_id=141981764", CHANNELS_URL)
+  );
+}
+
+#[test]
+fn token_handlers_keep_their_own_client() {
+  let (mut first, first_client) = tokens("first_handler");
+  let (mut second, second_client) = tokens("second_handler");
+  first_client.respond(GET_USERS_URL, 200, format!(r#"{{"data":[{}]}}"#, USER));
+  second_client.respond(GET_USERS_URL, 200, r#"{"data":[]}"#);
+
+  assert_eq!(
+    first
+      .get_users(vec!["141981764"], Vec::<String>::new())
+      .unwrap()
+      .data
+      .len(),
+    1
+  );
+  assert!(
+    second
+      .get_users(vec!["141981764"], Vec::<String>::new())
+      .unwrap()
+      .data
+      .is_empty()
+  );
+  assert_eq!(first_client.requests().len(), 1);
+  assert_eq!(second_client.requests().len(), 1);
+}
+
+#[test]
+fn get_streams_needs_a_user() {
+  let (mut tokens, client) = tokens("get_streams_for_no_one");
+  client.respond(GET_STREAMS_URL, 200, r#"{"data":[],"pagination":{}}"#);
+
+  assert!(
+    tokens
+      .get_streams_for_users(Vec::<String>::new(), Vec::<String>::new())
+      .unwrap()
+      .data
+      .is_empty()
+  );
+  assert!(matches!(
+    twitcheventsub_api::get_streams_for_users(
+      &tokens.http_client(),
+      &tokens.user_token,
+      &tokens.client_id,
+      &[] as &[&str],
+      &[] as &[&str],
+    ),
+    Err(TwitchApiError::InputError(_))
+  ));
+  assert!(client.requests().is_empty());
+}
@@ -9,6 +9,7 @@ use std::time::Duration;
 use modules::bttv::BTTV;
 pub use modules::errors::LOG_FILE;
 use modules::irc_bot::IRCChat;
+use modules::viewer_count::ViewerCountPoller;
 use tungstenite::connect;
 use twitcheventsub_api::{get_user_and_refresh_token_from_authorisation_code, TwitchApiError};
 use twitcheventsub_structs::prelude::{Subscription, TwitchEvent, UserData};
@@ -25,13 +26,20 @@ pub mod prelude {
 
   #[cfg(feature = "bevy")]
   pub use crate::modules::bevy::*;
-  pub use crate::modules::{bttv::*, emotebuilder::*};
+  pub use crate::modules::{
+    bttv::*, chat_queue::*, emotebuilder::*, hype_train::*, irc_bot::*, roster::*, shared_chat::*,
+    stream_session::*, viewer_count::*,
+  };
 }
 
 #[cfg(feature = "logging")]
 pub use log::{error, info, warn};
 
-pub use crate::modules::{emotebuilder::*, errors::EventSubError, eventsub};
+pub use crate::modules::{
+  emotebuilder::*,
+  errors::{EventSubError, IRCError},
+  eventsub,
+};
 
 impl From<TwitchApiError> for EventSubError {
   fn from(value: TwitchApiError) -> Self {
@@ -93,6 +101,7 @@ pub struct TwitchEventSubApi {
   tokens: TokenHandler,
   bot_tokens: Option<TokenHandler>,
   subscription_data: Vec<String>,
+  use_irc_channel: bool,
   pub bttv: BTTV,
   pub broadcaster_user: UserData,
 }
@@ -147,28 +156,31 @@ impl TwitchEventSubApi {
       )
     };
 
-    let bttv = BTTV::new(&broadcaster_user.id);
-    let bttv2 = BTTV::new(&broadcaster_user.id);
+    let bttv = BTTV::new_with_client(&broadcaster_user.id, &tokens.http_client());
+    let bttv2 = BTTV::new_with_client(&broadcaster_user.id, &tokens.http_client());
 
     let mut irc = None;
 
-    //if use_irc_channel {
-    //  subscriptions.append(&mut vec![
-    //    Subscription::PermissionIRCRead,
-    //    Subscription::PermissionIRCWrite,
-    //  ]);
-    //}
-
-    //if let Ok(false) = tokens.check_token_has_required_subscriptions(&subscriptions) {
-    //  tokens.generate_user_and_refresh_tokens(&subscriptions);
-    //}
-
-    // if use_irc_channel {
-    // let mut new_irc = IRCChat::new(&token_user.login, &tokens.user_token);
-    // new_irc.join_channel(&broadcaster_user.login);
-
-    // irc = Some(new_irc);
-    // }
+    if use_irc_channel {
+      // IRC is only read from, an anonymous connection is enough when the
+      // token can't read chat over IRC
+      let new_irc = if subscriptions.contains(&Subscription::PermissionIRCRead) {
+        IRCChat::new(&token_user.login, &tokens.user_token)
+      } else {
+        IRCChat::anonymous()
+      };
+
+      match new_irc {
+        Ok(mut new_irc) => {
+          new_irc.join_channel(&broadcaster_user.login);
+          irc = Some(new_irc);
+        }
+        Err(_e) => {
+          #[cfg(feature = "logging")]
+          warn!("Failed to connect to IRC, continuing without it: {:?}", _e);
+        }
+      }
+    }
 
     #[cfg(feature = "logging")]
     info!("Starting websocket client.");
@@ -203,6 +215,7 @@ impl TwitchEventSubApi {
       tokens,
       bot_tokens,
       subscription_data: custom_subscription_data,
+      use_irc_channel,
       bttv: bttv2,
       broadcaster_user,
     })
@@ -220,6 +233,12 @@ impl TwitchEventSubApi {
     &self.broadcaster_user
   }
 
+  /// Starts polling the broadcaster's viewer count with a copy of the tokens,
+  /// see [`ViewerCountPoller`].
+  pub fn poll_viewer_count(&self, interval: Duration) -> ViewerCountPoller {
+    ViewerCountPoller::start(self.tokens.clone(), &self.broadcaster_user.id, interval)
+  }
+
   pub fn restart_websockets(&mut self) -> Result<(), EventSubError> {
     let _ = self.send_quit_message.send(true);
 
@@ -231,7 +250,7 @@ impl TwitchEventSubApi {
       tokens,
       bot_tokens,
       custom_subscription_data,
-      false,
+      self.use_irc_channel,
       broadcasters_username,
     )?;
 
@@ -1,10 +1,12 @@
 //pub const BTTV_ENDPOINT: &str = "https://api.betterttv.net/3/";
 //pub const BTTV_USER: &str = "/cached/users/";
 
+use std::sync::Arc;
+
 use serde::Deserialize as Deserialise;
 use serde_json;
-use twitcheventsub_api::TwitchHttpRequest;
-use twitcheventsub_structs::prelude::{EmoteScale, EmoteUrl};
+use twitcheventsub_api::{HttpClient, TwitchHttpRequest, http_client};
+use twitcheventsub_structs::prelude::{EmoteScale, EmoteUrl, FragmentType, Fragments};
 
 pub const _BTTV_GLOBAL_EMOTES: &str = "https://api.betterttv.net/3/cached/emotes/global";
 pub const _BTTV_CHANNEL_EMOTES: &str =
@@ -25,13 +27,20 @@ pub struct BTTV {
 
 impl BTTV {
   pub fn new<S: Into<String>>(id: S) -> BTTV {
+    BTTV::new_with_client(id, &http_client())
+  }
+
+  /// Same as [`BTTV::new`] but requests the emotes with `http_client`.
+  pub fn new_with_client<S: Into<String>>(id: S, http_client: &Arc<dyn HttpClient>) -> BTTV {
     let mut bttv = BTTV {
       response: None,
       emote_names: Vec::new(),
     };
 
     if let Ok(user_emotes) =
-      TwitchHttpRequest::new(BTTV_CHANNEL_EMOTES_FROM_ID.replace("{id}", &id.into())).run()
+      TwitchHttpRequest::new(BTTV_CHANNEL_EMOTES_FROM_ID.replace("{id}", &id.into()))
+        .client(http_client)
+        .run()
     {
       //pikaOMG
       //825175324
@@ -85,6 +94,48 @@ impl BTTV {
 
     None
   }
+
+  /// Splits plain text fragments so every BTTV emote gets its own
+  /// [`FragmentType::BttvEmote`] fragment.
+  pub fn split_fragments(&self, fragments: &[Fragments]) -> Vec<Fragments> {
+    let mut split_fragments: Vec<Fragments> = Vec::new();
+    for fragment in fragments {
+      // Only check plain text for bttv emotes
+      if fragment.kind == FragmentType::Text {
+        let mut new_fragment: Fragments = fragment.clone();
+        new_fragment.text = String::new();
+        let text_particles = fragment.text.split(' ').collect::<Vec<_>>();
+
+        for test_text in text_particles {
+          if self.emote_names.contains(&test_text.to_lowercase()) {
+            if !new_fragment.text.is_empty() {
+              split_fragments.push(new_fragment);
+            }
+
+            new_fragment = fragment.clone();
+            // is BTTV emote
+            new_fragment.kind = FragmentType::BttvEmote;
+            new_fragment.text = test_text.to_lowercase().to_string();
+
+            split_fragments.push(new_fragment);
+
+            new_fragment = fragment.clone();
+            new_fragment.text = String::new();
+          } else {
+            new_fragment.text = format!("{}{} ", new_fragment.text, test_text);
+          }
+        }
+
+        if !new_fragment.text.is_empty() {
+          split_fragments.push(new_fragment);
+        }
+      } else {
+        split_fragments.push(fragment.clone());
+      }
+    }
+
+    split_fragments
+  }
 }
 
 #[derive(Deserialise, Debug, Clone)]
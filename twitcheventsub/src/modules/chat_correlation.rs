@@ -0,0 +1,67 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use twitcheventsub_structs::prelude::{MessageData, TwitchEvent};
+
+use super::irc_bot::{IRCMessage, PRIV_MESSAGE};
+
+/// How long an IRC message waits for its channel.chat.message event.
+pub const IRC_MESSAGE_LIFETIME: Duration = Duration::from_secs(30);
+/// How long a channel.chat.message event waits for its IRC message before
+/// being sent without the IRC only details.
+pub const CHAT_MESSAGE_WAIT: Duration = Duration::from_millis(1500);
+
+/// Pairs channel.chat.message events with their IRC PRIVMSG, the IRC `id`
+/// tag is the eventsub `message_id`.
+///
+/// Either side can arrive first, so both are buffered briefly. Chat messages
+/// are released in the order they were received.
+#[derive(Debug, Default)]
+pub struct ChatCorrelator {
+  irc_messages: HashMap<String, (Instant, IRCMessage)>,
+  pending: VecDeque<(Instant, Box<MessageData>)>,
+}
+
+impl ChatCorrelator {
+  pub fn add_irc_message(&mut self, message: IRCMessage) {
+    if message.command != PRIV_MESSAGE {
+      return;
+    }
+
+    if let Some(id) = message.id() {
+      self
+        .irc_messages
+        .insert(id.to_owned(), (Instant::now(), message));
+    }
+  }
+
+  pub fn add_chat_message(&mut self, message: Box<MessageData>) {
+    self.pending.push_back((Instant::now(), message));
+  }
+
+  /// Chat messages that either found their IRC message or waited long enough,
+  /// as [`TwitchEvent::ChatMessage`]s.
+  pub fn ready_messages(&mut self) -> Vec<TwitchEvent> {
+    self
+      .irc_messages
+      .retain(|_, (received, _)| received.elapsed() < IRC_MESSAGE_LIFETIME);
+
+    let mut ready = Vec::new();
+
+    while let Some((received, message)) = self.pending.front_mut() {
+      if let Some((_, irc_message)) = self.irc_messages.remove(&message.message_id) {
+        message.first_time_chatter = irc_message.first_time_chatter();
+        message.returning_chatter = irc_message.returning_chatter();
+        message.moderator |= irc_message.moderator();
+      } else if received.elapsed() < CHAT_MESSAGE_WAIT {
+        break;
+      }
+
+      if let Some((_, message)) = self.pending.pop_front() {
+        ready.push(TwitchEvent::ChatMessage(message));
+      }
+    }
+
+    ready
+  }
+}
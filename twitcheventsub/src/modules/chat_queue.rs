@@ -0,0 +1,439 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use twitcheventsub_api::TwitchApiError;
+use twitcheventsub_structs::prelude::SentChatMessages;
+use twitcheventsub_tokens::TokenHandler;
+
+use super::irc_bot::IRCChat;
+
+/// Twitch counts chat messages over a rolling 30 seconds.
+pub const CHAT_RATE_WINDOW: Duration = Duration::from_secs(30);
+pub const CHAT_MESSAGE_LIMIT: u32 = 20;
+/// Moderators and the broadcaster get a higher limit in their channel.
+pub const CHAT_MESSAGE_LIMIT_MODERATOR: u32 = 100;
+pub const MAX_CHAT_MESSAGE_LENGTH: usize = 500;
+pub const DEFAULT_CHAT_QUEUE_LENGTH: usize = 100;
+
+/// Remembers when every message in the last window went out, so no stretch
+/// of that length ever holds more than `limit` messages.
+#[derive(Clone, Debug)]
+pub struct SlidingWindow {
+  limit: u32,
+  window: Duration,
+  sent: VecDeque<Instant>,
+}
+
+impl SlidingWindow {
+  pub fn new(limit: u32, window: Duration) -> SlidingWindow {
+    SlidingWindow {
+      limit,
+      window,
+      sent: VecDeque::new(),
+    }
+  }
+
+  fn forget_expired(&mut self) {
+    while let Some(sent_at) = self.sent.front() &&
+      sent_at.elapsed() >= self.window
+    {
+      self.sent.pop_front();
+    }
+  }
+
+  pub fn try_take(&mut self) -> bool {
+    self.forget_expired();
+
+    if self.sent.len() < self.limit as usize {
+      self.sent.push_back(Instant::now());
+      true
+    } else {
+      false
+    }
+  }
+
+  pub fn available(&mut self) -> u32 {
+    self.forget_expired();
+    self.limit.saturating_sub(self.sent.len() as u32)
+  }
+
+  pub fn time_until_available(&mut self) -> Duration {
+    self.forget_expired();
+
+    if self.sent.len() < self.limit as usize {
+      return Duration::ZERO;
+    }
+
+    // Everything up to and including this one has to leave the window first
+    self
+      .sent
+      .get(self.sent.len() - self.limit as usize)
+      .map(|sent_at| self.window.saturating_sub(sent_at.elapsed()))
+      .unwrap_or_default()
+  }
+
+  /// Changes the limit, messages already sent still count against it.
+  pub fn set_limit(&mut self, limit: u32) {
+    self.limit = limit;
+  }
+}
+
+#[derive(Debug)]
+pub enum ChatSendResult {
+  Sent {
+    message: String,
+    message_id: String,
+  },
+  /// Twitch accepted the request but didn't send the message, `is_sent` was
+  /// false. Such as automod holding it or the channel being in followers only.
+  Rejected {
+    message: String,
+    message_id: String,
+    code: String,
+    reason: String,
+  },
+  /// Same as the last message within 30 seconds, twitch drops these for
+  /// anyone that isn't a moderator.
+  Duplicate(String),
+  TooLong(String),
+  QueueFull(String),
+  Failed {
+    message: String,
+    error: TwitchApiError,
+  },
+}
+
+impl ChatSendResult {
+  pub fn is_sent(&self) -> bool {
+    matches!(self, ChatSendResult::Sent { .. })
+  }
+}
+
+#[derive(Clone, Debug)]
+struct QueuedChatMessage {
+  message: String,
+  reply_to_message_id: Option<String>,
+}
+
+/// Sends chat messages in order without going over twitch's chat limits,
+/// anything over the limit waits in the queue until older messages leave the
+/// 30 second window.
+///
+/// Call [`ChatMessageQueue::send_ready`] or
+/// [`ChatMessageQueue::send_ready_irc`] regularly, such as every frame or
+/// after receiving messages.
+#[derive(Clone, Debug)]
+pub struct ChatMessageQueue {
+  broadcaster_id: String,
+  is_moderator: bool,
+  limiter: SlidingWindow,
+  queue: VecDeque<QueuedChatMessage>,
+  max_queue_length: usize,
+  last_sent: Option<(String, Instant)>,
+}
+
+impl ChatMessageQueue {
+  pub fn new<S: Into<String>>(broadcaster_id: S, is_moderator: bool) -> ChatMessageQueue {
+    ChatMessageQueue {
+      broadcaster_id: broadcaster_id.into(),
+      is_moderator,
+      limiter: SlidingWindow::new(ChatMessageQueue::limit(is_moderator), CHAT_RATE_WINDOW),
+      queue: VecDeque::new(),
+      max_queue_length: DEFAULT_CHAT_QUEUE_LENGTH,
+      last_sent: None,
+    }
+  }
+
+  fn limit(is_moderator: bool) -> u32 {
+    if is_moderator {
+      CHAT_MESSAGE_LIMIT_MODERATOR
+    } else {
+      CHAT_MESSAGE_LIMIT
+    }
+  }
+
+  pub fn with_max_queue_length(mut self, max_queue_length: usize) -> ChatMessageQueue {
+    self.max_queue_length = max_queue_length;
+    self
+  }
+
+  /// Moderator status can change mid stream, keep it up to date from
+  /// [`crate::prelude::RosterCache::can_moderate`].
+  pub fn set_moderator(&mut self, is_moderator: bool) {
+    if self.is_moderator != is_moderator {
+      self.is_moderator = is_moderator;
+      self
+        .limiter
+        .set_limit(ChatMessageQueue::limit(is_moderator));
+    }
+  }
+
+  pub fn is_moderator(&self) -> bool {
+    self.is_moderator
+  }
+
+  /// Returns the result straight away if the message can never be sent.
+  pub fn queue_message<S: Into<String>>(&mut self, message: S) -> Option<ChatSendResult> {
+    self.push(message.into(), None)
+  }
+
+  pub fn queue_reply<S: Into<String>>(
+    &mut self,
+    message: S,
+    reply_to_message_id: &str,
+  ) -> Option<ChatSendResult> {
+    self.push(message.into(), Some(reply_to_message_id.to_owned()))
+  }
+
+  fn push(
+    &mut self,
+    message: String,
+    reply_to_message_id: Option<String>,
+  ) -> Option<ChatSendResult> {
+    // Twitch counts characters, not bytes
+    if message.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+      return Some(ChatSendResult::TooLong(message));
+    }
+
+    if self.queue.len() >= self.max_queue_length {
+      return Some(ChatSendResult::QueueFull(message));
+    }
+
+    self.queue.push_back(QueuedChatMessage {
+      message,
+      reply_to_message_id,
+    });
+
+    None
+  }
+
+  pub fn len(&self) -> usize {
+    self.queue.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.queue.is_empty()
+  }
+
+  pub fn clear(&mut self) {
+    self.queue.clear();
+  }
+
+  /// How long until the next queued message can go out.
+  pub fn time_until_next_send(&mut self) -> Duration {
+    self.limiter.time_until_available()
+  }
+
+  /// Sends as many queued messages through Helix as the limit allows.
+  pub fn send_ready(&mut self, tokens: &mut TokenHandler) -> Vec<ChatSendResult> {
+    let broadcaster_id = self.broadcaster_id.clone();
+
+    self.send_with(|queued| {
+      let response = tokens.send_chat_message_with_reply(
+        &broadcaster_id,
+        &queued.message,
+        queued.reply_to_message_id.clone(),
+      );
+
+      let sent = response.and_then(|data| {
+        serde_json::from_str::<SentChatMessages>(&data)
+          .map_err(|e| TwitchApiError::DeserialisationError(e.to_string()))
+      });
+
+      match sent.map(|sent| sent.data.into_iter().next()) {
+        Ok(Some(sent)) if sent.is_sent => ChatSendResult::Sent {
+          message: queued.message,
+          message_id: sent.message_id,
+        },
+        Ok(Some(sent)) => {
+          let drop_reason = sent.drop_reason;
+          ChatSendResult::Rejected {
+            message: queued.message,
+            message_id: sent.message_id,
+            code: drop_reason
+              .as_ref()
+              .map(|reason| reason.code.clone())
+              .unwrap_or_default(),
+            reason: drop_reason.map(|reason| reason.message).unwrap_or_default(),
+          }
+        }
+        Ok(None) => ChatSendResult::Failed {
+          message: queued.message,
+          error: TwitchApiError::DeserialisationError(String::from(
+            "Send chat message response had no data.",
+          )),
+        },
+        Err(error) => ChatSendResult::Failed {
+          message: queued.message,
+          error,
+        },
+      }
+    })
+  }
+
+  /// Sends as many queued messages over IRC as the limit allows, IRC gives
+  /// no message id back.
+  pub fn send_ready_irc(&mut self, irc: &mut IRCChat) -> Vec<ChatSendResult> {
+    self.send_with(|queued| {
+      match &queued.reply_to_message_id {
+        Some(reply_to_message_id) => irc.reply_to_message(reply_to_message_id, &queued.message),
+        None => irc.send_message(&queued.message),
+      }
+
+      ChatSendResult::Sent {
+        message: queued.message,
+        message_id: String::new(),
+      }
+    })
+  }
+
+  fn send_with<F>(&mut self, mut send: F) -> Vec<ChatSendResult>
+  where
+    F: FnMut(QueuedChatMessage) -> ChatSendResult,
+  {
+    let mut results = Vec::new();
+
+    while let Some(queued) = self.queue.front() {
+      if !self.is_moderator &&
+        self
+          .last_sent
+          .as_ref()
+          .is_some_and(|(last_message, sent_at)| {
+            *last_message == queued.message && sent_at.elapsed() < CHAT_RATE_WINDOW
+          })
+      {
+        if let Some(queued) = self.queue.pop_front() {
+          results.push(ChatSendResult::Duplicate(queued.message));
+        }
+        continue;
+      }
+
+      if !self.limiter.try_take() {
+        break;
+      }
+
+      let Some(queued) = self.queue.pop_front() else {
+        break;
+      };
+      let message = queued.message.clone();
+      let result = send(queued);
+
+      if result.is_sent() {
+        self.last_sent = Some((message, Instant::now()));
+      }
+
+      results.push(result);
+    }
+
+    results
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::thread;
+
+  use super::*;
+
+  fn sent(queued: QueuedChatMessage) -> ChatSendResult {
+    ChatSendResult::Sent {
+      message: queued.message,
+      message_id: String::new(),
+    }
+  }
+
+  fn sent_count(results: &[ChatSendResult]) -> usize {
+    results.iter().filter(|result| result.is_sent()).count()
+  }
+
+  #[test]
+  fn sliding_window_holds_at_most_the_limit() {
+    let mut window = SlidingWindow::new(2, Duration::from_millis(50));
+
+    assert_eq!(window.available(), 2);
+    assert!(window.time_until_available().is_zero());
+    assert!(window.try_take());
+    assert!(window.try_take());
+    assert!(!window.try_take());
+    assert_eq!(window.available(), 0);
+    assert!(window.time_until_available() > Duration::ZERO);
+
+    thread::sleep(Duration::from_millis(60));
+
+    assert_eq!(window.available(), 2);
+    assert!(window.try_take());
+  }
+
+  #[test]
+  fn sliding_window_lower_limit_counts_sent_messages() {
+    let mut window = SlidingWindow::new(3, Duration::from_secs(30));
+
+    assert!(window.try_take());
+    assert!(window.try_take());
+    window.set_limit(1);
+
+    assert_eq!(window.available(), 0);
+    assert!(!window.try_take());
+  }
+
+  #[test]
+  fn queue_waits_at_the_limit_and_switches_for_moderators() {
+    let mut queue = ChatMessageQueue::new("1337", false);
+    for i in 0..CHAT_MESSAGE_LIMIT + 10 {
+      assert!(queue.queue_message(format!("message {}", i)).is_none());
+    }
+
+    let results = queue.send_with(sent);
+    assert_eq!(sent_count(&results), CHAT_MESSAGE_LIMIT as usize);
+    assert_eq!(queue.len(), 10);
+    assert!(queue.send_with(sent).is_empty());
+    assert!(queue.time_until_next_send() > Duration::ZERO);
+
+    queue.set_moderator(true);
+    assert!(queue.is_moderator());
+
+    let results = queue.send_with(sent);
+    assert_eq!(sent_count(&results), 10);
+    assert!(queue.is_empty());
+  }
+
+  #[test]
+  fn repeated_message_is_dropped_unless_moderator() {
+    let mut queue = ChatMessageQueue::new("1337", false);
+    queue.queue_message("hello");
+    queue.queue_message("hello");
+    queue.queue_message("there");
+
+    let results = queue.send_with(sent);
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_sent());
+    assert!(matches!(&results[1], ChatSendResult::Duplicate(message) if message == "hello"));
+    assert!(results[2].is_sent());
+
+    let mut queue = ChatMessageQueue::new("1337", true);
+    queue.queue_message("hello");
+    queue.queue_message("hello");
+
+    assert_eq!(sent_count(&queue.send_with(sent)), 2);
+  }
+
+  #[test]
+  fn long_message_and_full_queue_are_returned_straight_away() {
+    let mut queue = ChatMessageQueue::new("1337", false).with_max_queue_length(1);
+
+    assert!(matches!(
+      queue.queue_message("a".repeat(MAX_CHAT_MESSAGE_LENGTH + 1)),
+      Some(ChatSendResult::TooLong(_))
+    ));
+    // Counted in characters, each of these is several bytes
+    assert!(
+      queue
+        .queue_message("\u{1F600}".repeat(MAX_CHAT_MESSAGE_LENGTH))
+        .is_none()
+    );
+    assert!(matches!(
+      queue.queue_message("hello"),
+      Some(ChatSendResult::QueueFull(_))
+    ));
+  }
+}
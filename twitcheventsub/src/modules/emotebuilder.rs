@@ -5,6 +5,9 @@ use twitcheventsub_tokens::TokenHandler;
 
 use super::bttv::BTTV;
 
+pub const TWITCH_EMOTE_CDN: &str =
+  "https://static-cdn.jtvnw.net/emoticons/v2/{{id}}/{{format}}/{{theme_mode}}/{{scale}}";
+
 pub struct EmoteBuilder {
   scale: EmoteScale,
   theme: ThemeMode,
@@ -102,6 +105,7 @@ impl EmoteBuilder {
         }
 
         if let Ok(channel_emotes) = twitcheventsub_api::get_channel_emotes(
+          &tokens.http_client(),
           &tokens.user_token,
           &tokens.client_id,
           broadcaser_id,
@@ -119,38 +123,44 @@ impl EmoteBuilder {
           }
         }
 
-        if emote_data.is_none() {
-          if let Ok(emote_sets) = twitcheventsub_api::get_emote_set(
-            &fragment.emote.as_ref().unwrap().emote_set_id.to_owned(),
+        let emote_set_id = &fragment.emote.as_ref().unwrap().emote_set_id;
+        // Emotes parsed from IRC don't know their emote set
+        if emote_data.is_none() &&
+          !emote_set_id.is_empty() &&
+          let Ok(emote_sets) = twitcheventsub_api::get_emote_set(
+            &tokens.http_client(),
+            emote_set_id,
             &tokens.user_token,
             &tokens.client_id,
-          ) {
-            template = emote_sets.template;
-
-            let mut valid_emotes = emote_sets
-              .data
-              .into_iter()
-              .filter_map(|d| if d.id == real_id { Some(d) } else { None })
-              .collect::<Vec<_>>();
-            if !valid_emotes.is_empty() {
-              emote_data = Some(valid_emotes.remove(0).into());
-            }
+          )
+        {
+          template = emote_sets.template;
+
+          let mut valid_emotes = emote_sets
+            .data
+            .into_iter()
+            .filter_map(|d| if d.id == real_id { Some(d) } else { None })
+            .collect::<Vec<_>>();
+          if !valid_emotes.is_empty() {
+            emote_data = Some(valid_emotes.remove(0).into());
           }
+        }
 
-          if emote_data.is_none() {
-            if let Ok(global_emotes) =
-              twitcheventsub_api::get_global_emotes(&tokens.user_token, &tokens.client_id)
-            {
-              template = global_emotes.template;
-              let mut valid_emotes = global_emotes
-                .data
-                .into_iter()
-                .filter_map(|d| if d.id == real_id { Some(d) } else { None })
-                .collect::<Vec<_>>();
-              if !valid_emotes.is_empty() {
-                emote_data = Some(valid_emotes.remove(0).into());
-              }
-            }
+        if emote_data.is_none() &&
+          let Ok(global_emotes) = twitcheventsub_api::get_global_emotes(
+            &tokens.http_client(),
+            &tokens.user_token,
+            &tokens.client_id,
+          )
+        {
+          template = global_emotes.template;
+          let mut valid_emotes = global_emotes
+            .data
+            .into_iter()
+            .filter_map(|d| if d.id == real_id { Some(d) } else { None })
+            .collect::<Vec<_>>();
+          if !valid_emotes.is_empty() {
+            emote_data = Some(valid_emotes.remove(0).into());
           }
         }
 
@@ -178,11 +188,40 @@ impl EmoteBuilder {
             animated: self.format == EmoteFormat::Animated,
           })
         } else {
-          None
+          self.cdn_emote_url(fragment)
         }
       }
       FragmentType::BttvEmote => bttv.get_emote_url(&fragment.text, &self.scale),
       _ => None,
     }
   }
+
+  /// Same as [`EmoteBuilder::build`] without any api calls, for anonymous IRC
+  /// where there is no token. Twitch emotes come straight from the cdn.
+  pub fn build_without_token(&mut self, fragment: &Fragments, bttv: &mut BTTV) -> Option<EmoteUrl> {
+    match fragment.kind {
+      FragmentType::Emote => self.cdn_emote_url(fragment),
+      FragmentType::BttvEmote => bttv.get_emote_url(&fragment.text, &self.scale),
+      _ => None,
+    }
+  }
+
+  // Without api access (such as anonymous IRC) the emote can still be
+  // fetched straight from the cdn, only the static format is certain
+  fn cdn_emote_url(&self, fragment: &Fragments) -> Option<EmoteUrl> {
+    if self.format != EmoteFormat::Static && !self.fallback_on_format_missing {
+      return None;
+    }
+
+    let url = TWITCH_EMOTE_CDN
+      .replace("{{id}}", &fragment.emote.as_ref()?.id)
+      .replace("{{format}}", &EmoteFormat::Static.string())
+      .replace("{{theme_mode}}", &self.theme.string())
+      .replace("{{scale}}", &format!("{}.0", self.scale.idx() + 1));
+
+    Some(EmoteUrl {
+      url,
+      animated: false,
+    })
+  }
 }
@@ -13,9 +13,15 @@ pub enum EventSubError {
   MessageTooLong,
   UnhandledError(String),
   WriteError(String),
-  CurlFailed(curl::Error),
   HttpFailed(String),
   ParseError(String),
   MaximumWebsocketTransmissionsExceeded(String),
   TwitchApiError(TwitchApiError),
+  IRCError(IRCError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum IRCError {
+  ConnectionFailed(String),
+  AuthenticationFailed(String),
 }
@@ -17,10 +17,26 @@ use twitcheventsub_structs::prelude::{
 };
 use twitcheventsub_tokens::TokenHandler;
 
+use super::chat_correlation::ChatCorrelator;
 use super::irc_bot::IRCChat;
+use super::stream_session::StreamSessionTracker;
 use super::{bttv::BTTV, irc_bot};
 use crate::{CONNECTION_EVENTS, EventSubError, ResponseType, SUBSCRIBE_URL};
 
+/// How often the eventsub read gives up waiting so IRC messages are handled.
+const IRC_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub(crate) fn set_read_timeout(
+  socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+  timeout: Option<Duration>,
+) {
+  let _ = match socket.get_mut() {
+    MaybeTlsStream::Plain(stream) => stream.set_read_timeout(timeout),
+    MaybeTlsStream::NativeTls(stream) => stream.get_mut().set_read_timeout(timeout),
+    _ => Ok(()),
+  };
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn events(
   mut twitch_receiver: WebSocket<MaybeTlsStream<TcpStream>>, //Client<TlsStream<TcpStream>>>>,
@@ -42,38 +58,80 @@ pub fn events(
 
   use std::sync::mpsc::channel;
 
-  use twitcheventsub_structs::prelude::{FragmentType, Fragments};
-
-  use crate::modules::irc_bot::{IRCMessage, IRCResponse};
+  use crate::modules::irc_bot::IRCResponse;
 
   let mut last_message = Instant::now();
 
   let mut is_reconnecting = false;
 
   let mut messages_from_irc = None;
+  // Dropped when this thread returns, which stops the IRC thread too
+  let mut _stop_irc = None;
   if let Some(irc) = irc {
     let (transmit_messages, receive_message) = channel();
+    let (stop_irc, should_stop_irc) = channel();
 
     let _ = thread::spawn(move || {
-      irc_bot::irc_thread(irc, transmit_messages);
+      irc_bot::irc_thread(irc, transmit_messages, should_stop_irc);
     });
 
     messages_from_irc = Some(receive_message);
+    _stop_irc = Some(stop_irc);
   }
 
-  let mut irc_messages: Vec<(Instant, IRCMessage)> = Vec::new();
+  let mut chat_correlator = ChatCorrelator::default();
+  // Reads have to time out so chat messages waiting on IRC still get sent
+  let read_timeout = messages_from_irc.as_ref().map(|_| IRC_POLL_INTERVAL);
+  set_read_timeout(&mut twitch_receiver, read_timeout);
+
+  let tracks_stream_session = subscriptions
+    .iter()
+    .any(|s| matches!(s, Subscription::StreamOnline | Subscription::StreamOffline));
+  let mut stream_session: Option<StreamSessionTracker> = None;
 
   loop {
     if let Ok(true) = should_quit_receiver.try_recv() {
       return;
     }
 
+    while let Some(irc_reciever) = &messages_from_irc {
+      match irc_reciever.recv_timeout(Duration::ZERO) {
+        Ok(IRCResponse::IRCMessage(msg)) => chat_correlator.add_irc_message(msg),
+        Ok(IRCResponse::Error(e)) => {
+          let _ = message_sender.send(ResponseType::Error(Box::new(EventSubError::IRCError(e))));
+          messages_from_irc = None;
+        }
+        _ => break,
+      }
+    }
+
+    for chat_message in chat_correlator.ready_messages() {
+      let _ = message_sender.send(ResponseType::Event(Box::new(chat_message)));
+    }
+
+    // Checked before reading, timed out reads skip the rest of the loop
+    if last_message.elapsed().as_secs() > 60 {
+      let _ = twitch_receiver.send(NetworkMessage::Close(None));
+      thread::sleep(Duration::from_secs(5));
+      #[cfg(feature = "logging")]
+      error!("Messages not sent within the keep alive timeout restarting websocket");
+      let (new_client, _) = connect(CONNECTION_EVENTS)
+        .expect("Failed to reconnect to new url after receiving reconnect message from twitch");
+      twitch_receiver = new_client;
+      set_read_timeout(&mut twitch_receiver, read_timeout);
+      last_message = Instant::now();
+      is_reconnecting = false;
+      continue;
+    }
+
     let message = match twitch_receiver.read() {
       Ok(m) => m,
-      Err(Error::Io(e)) if e.kind() == ErrorKind::WouldBlock => {
-        // shouldn't happen
-        #[cfg(feature = "logging")]
-        error!("EventSub: Would block");
+      Err(Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+        if read_timeout.is_none() {
+          // shouldn't happen
+          #[cfg(feature = "logging")]
+          error!("EventSub: Would block");
+        }
         continue;
       }
       Err(Error::ConnectionClosed) | Err(Error::AlreadyClosed) => {
@@ -87,6 +145,7 @@ pub fn events(
         let (new_client, _) = connect(CONNECTION_EVENTS)
           .expect("Failed to reconnect to new url after receiving reconnect message from twitch");
         twitch_receiver = new_client;
+        set_read_timeout(&mut twitch_receiver, read_timeout);
         last_message = Instant::now();
         is_reconnecting = false;
         continue;
@@ -99,32 +158,6 @@ pub fn events(
       }
     };
 
-    while let Some(irc_reciever) = &messages_from_irc {
-      match irc_reciever.recv_timeout(Duration::ZERO) {
-        Ok(IRCResponse::IRCMessage(msg)) => irc_messages.push((Instant::now(), msg)),
-        _ => break,
-      }
-    }
-
-    if last_message.elapsed().as_secs() > 60 {
-      let _ = twitch_receiver.send(NetworkMessage::Close(None));
-      thread::sleep(Duration::from_secs(5));
-      #[cfg(feature = "logging")]
-      error!("Messages not sent within the keep alive timeout restarting websocket");
-      let (new_client, _) = connect(CONNECTION_EVENTS)
-        .expect("Failed to reconnect to new url after receiving reconnect message from twitch");
-      twitch_receiver = new_client;
-      last_message = Instant::now();
-      is_reconnecting = false;
-      continue;
-    }
-
-    for i in (0..irc_messages.len()).rev() {
-      if irc_messages[i].0.elapsed().as_secs() > 30 {
-        irc_messages.remove(i);
-      }
-    }
-
     match message {
       NetworkMessage::Text(msg) => {
         #[cfg(feature = "only_raw_responses")]
@@ -132,7 +165,7 @@ pub fn events(
           let _ = message_sender.send(ResponseType::RawResponse(msg.to_string()));
         }
 
-        let message = serde_json::from_str(msg.as_str());
+        let message = GenericMessage::from_json(msg.as_str());
 
         if let Err(e) = message {
           #[cfg(feature = "logging")]
@@ -142,7 +175,7 @@ pub fn events(
           continue;
         }
 
-        let message: GenericMessage = message.unwrap();
+        let message = message.unwrap();
 
         match message.event_type() {
           EventMessageType::Welcome => {
@@ -171,13 +204,14 @@ pub fn events(
               let failed_to_communicate_with_main_thread = sub_data
                 .iter()
                 .map(|sub_data| {
-                  TwitchHttpRequest::new(SUBSCRIBE_URL)
+                  let response = TwitchHttpRequest::new(SUBSCRIBE_URL)
+                    .client(&tokens.http_client())
                     .full_auth(&user_token, &client_id)
                     .json_content()
                     .is_post(sub_data)
-                    .run()
+                    .run();
+                  tokens.regen_tokens_on_fail(response)
                 })
-                .map(|a| tokens.regen_tokens_on_fail(a))
                 .filter_map(Result::err)
                 .any(|error| {
                   #[cfg(feature = "logging")]
@@ -200,6 +234,27 @@ pub fn events(
               message_sender
                 .send(ResponseType::Ready)
                 .expect("Failed to send ready back to main thread.");
+
+              if tracks_stream_session && stream_session.is_none() {
+                match StreamSessionTracker::new(&mut tokens, broadcasters_users_id) {
+                  Ok(tracker) => {
+                    if let Some(session) = tracker.session() {
+                      let _ = message_sender.send(ResponseType::Event(Box::new(
+                        TwitchEvent::SessionStarted(session.clone()),
+                      )));
+                    }
+                    stream_session = Some(tracker);
+                  }
+                  Err(e) => {
+                    #[cfg(feature = "logging")]
+                    error!("EventSub: Failed to get current stream session: {:?}", e);
+                    let _ = message_sender.send(ResponseType::Error(Box::new(
+                      EventSubError::TwitchApiError(e),
+                    )));
+                    stream_session = Some(StreamSessionTracker::default());
+                  }
+                }
+              }
             }
             is_reconnecting = false;
             last_message = Instant::now();
@@ -227,6 +282,7 @@ pub fn events(
               "Failed to reconnect to new url after recieving reconnect message from twitch.",
             );
             twitch_receiver = new_client;
+            set_read_timeout(&mut twitch_receiver, read_timeout);
           }
           EventMessageType::Notification => {
             #[cfg(feature = "only_raw_responses")]
@@ -236,60 +292,33 @@ pub fn events(
             let mut message = message.payload.unwrap().event.unwrap();
 
             if let TwitchEvent::ChatMessage(msg) = &mut message {
-              for (_, irc_message) in irc_messages.iter() {
-                if irc_message.display_name == msg.chatter.name &&
-                  irc_message.message.contains(&msg.message.text)
-                {
-                  msg.returning_chatter = irc_message.returning_chatter;
-                  msg.first_time_chatter = irc_message.first_time_chatter;
-                  msg.moderator = msg
-                    .badges
-                    .iter()
-                    .any(|badge| badge.set_id.contains("moderator"));
-                  break;
-                }
-              }
-
-              let mut fragments: Vec<Fragments> = Vec::new();
-              for fragment in &mut msg.message.fragments {
-                // Only check plain text for bttv emotes
-                if fragment.kind == FragmentType::Text {
-                  let mut new_fragment: Fragments = fragment.clone();
-                  new_fragment.text = String::new();
-                  let text_particles = fragment.text.split(' ').collect::<Vec<_>>();
-
-                  for test_text in text_particles {
-                    if bttv.emote_names.contains(&test_text.to_lowercase()) {
-                      if !new_fragment.text.is_empty() {
-                        fragments.push(new_fragment);
-                      }
-
-                      new_fragment = fragment.clone();
-                      // is BTTV emote
-                      new_fragment.kind = FragmentType::BttvEmote;
-                      new_fragment.text = test_text.to_lowercase().to_string();
-
-                      fragments.push(new_fragment);
-
-                      new_fragment = fragment.clone();
-                      new_fragment.text = String::new();
-                    } else {
-                      new_fragment.text = format!("{}{} ", new_fragment.text, test_text);
-                    }
-                  }
+              msg.moderator = msg
+                .badges
+                .iter()
+                .any(|badge| badge.set_id.contains("moderator"));
+              msg.message.fragments = bttv.split_fragments(&msg.message.fragments);
+            }
 
-                  if !new_fragment.text.is_empty() {
-                    fragments.push(new_fragment);
-                  }
-                } else {
-                  fragments.push(fragment.clone());
+            let message = match message {
+              TwitchEvent::ChatMessage(msg) if messages_from_irc.is_some() => {
+                chat_correlator.add_chat_message(msg);
+                for chat_message in chat_correlator.ready_messages() {
+                  let _ = message_sender.send(ResponseType::Event(Box::new(chat_message)));
                 }
+                continue;
               }
+              message => message,
+            };
 
-              msg.message.fragments = fragments;
-            }
+            let session_event = stream_session
+              .as_mut()
+              .and_then(|tracker| tracker.update(&message));
 
             let _ = message_sender.send(ResponseType::Event(Box::new(message)));
+
+            if let Some(session_event) = session_event {
+              let _ = message_sender.send(ResponseType::Event(Box::new(session_event)));
+            }
           }
           EventMessageType::Unknown => {
             #[cfg(feature = "logging")]
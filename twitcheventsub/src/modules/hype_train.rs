@@ -0,0 +1,213 @@
+use std::time::{Duration, SystemTime};
+
+use twitcheventsub_api::TwitchApiError;
+use twitcheventsub_structs::prelude::{
+  Broadcaster, HypeTrainEndData, HypeTrainProgressData, HypeTrainRecord, TopContributions,
+  TwitchEvent, parse_timestamp,
+};
+use twitcheventsub_tokens::TokenHandler;
+
+/// Keeps track of the current hype train from the channel.hype_train.begin,
+/// progress and end events.
+///
+/// Seeded from Get Hype Train Status, so restarting mid train still picks
+/// up where it was.
+#[derive(Clone, Debug, Default)]
+pub struct HypeTrainTracker {
+  train: Option<HypeTrainProgressData>,
+  last_train: Option<HypeTrainEndData>,
+  all_time_high: Option<HypeTrainRecord>,
+  shared_all_time_high: Option<HypeTrainRecord>,
+}
+
+impl HypeTrainTracker {
+  pub fn new(
+    tokens: &mut TokenHandler,
+    broadcaster_id: &str,
+  ) -> Result<HypeTrainTracker, TwitchApiError> {
+    let mut tracker = HypeTrainTracker::default();
+
+    if let Some(status) = tokens
+      .get_hype_train_status(broadcaster_id)?
+      .data
+      .into_iter()
+      .next()
+    {
+      tracker.train = status.current;
+      tracker.all_time_high = status.all_time_high;
+      tracker.shared_all_time_high = status.shared_all_time_high;
+    }
+
+    Ok(tracker)
+  }
+
+  /// Feed every received event through here, returns true if the train changed.
+  pub fn update(&mut self, event: &TwitchEvent) -> bool {
+    match event {
+      TwitchEvent::HypeTrainBegin(begin) => {
+        if self.all_time_high.is_none() {
+          self.all_time_high = Some(HypeTrainRecord {
+            level: begin.all_time_high_level,
+            total: begin.all_time_high_total,
+            achieved_at: String::new(),
+          });
+        }
+        self.train = Some(begin.clone().into());
+        true
+      }
+      TwitchEvent::HypeTrainProgress(progress) => {
+        self.train = Some(progress.clone());
+        true
+      }
+      TwitchEvent::HypeTrainEnd(end) => {
+        let record = if end.is_shared_train {
+          &mut self.shared_all_time_high
+        } else {
+          &mut self.all_time_high
+        };
+
+        if record
+          .as_ref()
+          .is_none_or(|record| end.total > record.total)
+        {
+          *record = Some(HypeTrainRecord {
+            level: end.level,
+            total: end.total,
+            achieved_at: end.ended_at.clone(),
+          });
+        }
+
+        self.train = None;
+        self.last_train = Some(end.clone());
+        true
+      }
+      _ => false,
+    }
+  }
+
+  /// The train in progress, trains that expired without an end event are ignored.
+  pub fn current(&self) -> Option<&HypeTrainProgressData> {
+    self.train.as_ref().filter(|train| {
+      parse_timestamp(&train.expires_at).is_none_or(|expires_at| expires_at > SystemTime::now())
+    })
+  }
+
+  pub fn is_active(&self) -> bool {
+    self.current().is_some()
+  }
+
+  pub fn level(&self) -> Option<u32> {
+    self.current().map(|train| train.level)
+  }
+
+  /// How far through the current level the train is, from 0 to 100.
+  pub fn progress_percentage(&self) -> Option<f32> {
+    self.current().map(|train| {
+      if train.goal == 0 {
+        0.0
+      } else {
+        (train.progress as f32 / train.goal as f32 * 100.0).min(100.0)
+      }
+    })
+  }
+
+  pub fn time_remaining(&self) -> Option<Duration> {
+    self
+      .current()
+      .and_then(|train| parse_timestamp(&train.expires_at))
+      .and_then(|expires_at| expires_at.duration_since(SystemTime::now()).ok())
+  }
+
+  pub fn top_contributors(&self) -> &[TopContributions] {
+    self
+      .current()
+      .map(|train| train.top_contributions.as_slice())
+      .unwrap_or_default()
+  }
+
+  pub fn is_shared_train(&self) -> bool {
+    self.current().is_some_and(|train| train.is_shared_train)
+  }
+
+  pub fn shared_train_participants(&self) -> &[Broadcaster] {
+    self
+      .current()
+      .map(|train| train.shared_train_participants.as_slice())
+      .unwrap_or_default()
+  }
+
+  /// The most recently finished train, useful for the cooldown.
+  pub fn last_train(&self) -> Option<&HypeTrainEndData> {
+    self.last_train.as_ref()
+  }
+
+  pub fn all_time_high(&self) -> Option<&HypeTrainRecord> {
+    self.all_time_high.as_ref()
+  }
+
+  pub fn shared_all_time_high(&self) -> Option<&HypeTrainRecord> {
+    self.shared_all_time_high.as_ref()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use twitcheventsub_structs::prelude::{HypeTrainType, User};
+
+  use super::*;
+
+  fn end(total: u32, is_shared_train: bool) -> TwitchEvent {
+    TwitchEvent::HypeTrainEnd(HypeTrainEndData {
+      id: String::from("1b0AsbInCHZW2SQFQkCzqN07Ib2"),
+      broadcaster: User {
+        id: String::from("1337"),
+        name: String::from("Cool_User"),
+        login: String::from("cool_user"),
+      },
+      level: 2,
+      total,
+      top_contributions: Vec::new(),
+      shared_train_participants: Vec::new(),
+      started_at: String::from("2020-07-15T17:16:03.17106713Z"),
+      ended_at: String::from("2020-07-15T17:16:11.17106713Z"),
+      cooldown_ends_at: String::from("2020-07-15T18:16:11.17106713Z"),
+      kind: HypeTrainType::Regular,
+      is_shared_train,
+    })
+  }
+
+  #[test]
+  fn end_sets_the_first_all_time_high() {
+    let mut tracker = HypeTrainTracker::default();
+
+    assert!(tracker.update(&end(137, false)));
+    let record = tracker.all_time_high().unwrap();
+    assert_eq!(record.total, 137);
+    assert_eq!(record.level, 2);
+    assert_eq!(record.achieved_at, "2020-07-15T17:16:11.17106713Z");
+    assert!(tracker.shared_all_time_high().is_none());
+    assert!(!tracker.is_active());
+    assert_eq!(tracker.last_train().unwrap().total, 137);
+  }
+
+  #[test]
+  fn end_only_replaces_a_lower_all_time_high() {
+    let mut tracker = HypeTrainTracker::default();
+
+    tracker.update(&end(500, false));
+    tracker.update(&end(300, false));
+    assert_eq!(tracker.all_time_high().unwrap().total, 500);
+
+    tracker.update(&end(900, false));
+    assert_eq!(tracker.all_time_high().unwrap().total, 900);
+  }
+
+  #[test]
+  fn shared_train_end_updates_the_shared_all_time_high() {
+    let mut tracker = HypeTrainTracker::default();
+
+    tracker.update(&end(400, true));
+    assert_eq!(tracker.shared_all_time_high().unwrap().total, 400);
+    assert!(tracker.all_time_high().is_none());
+  }
+}
@@ -1,201 +1,874 @@
-use std::sync::mpsc::Sender as SyncSender;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::sync::mpsc::{Receiver as SyncReceiver, Sender as SyncSender, TryRecvError};
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{net::TcpStream, thread, time::Duration};
 
 #[cfg(feature = "logging")]
 use log::{error, info, warn};
-use tungstenite::{
-  connect, error::ProtocolError, stream::MaybeTlsStream, Error, Message as NetworkMessage,
-  WebSocket,
+use tungstenite::{connect, stream::MaybeTlsStream, Error, Message as NetworkMessage, WebSocket};
+
+use twitcheventsub_structs::prelude::{
+  Badge, Cheer, Emote, FragmentType, Fragments, Message, MessageData, MessageType, OptionalUser,
+  Reply, User,
 };
 
+use super::eventsub::set_read_timeout;
+use crate::modules::errors::IRCError;
+
 pub const PRIV_MESSAGE: &str = "PRIVMSG";
 pub const PASS: &str = "PASS";
 pub const NICK: &str = "NICK";
 pub const JOIN: &str = "JOIN";
+pub const PART: &str = "PART";
 
 pub const IRC_URL: &str = "ws://irc-ws.chat.twitch.tv:80";
 
-pub enum IRCResponse {
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+/// How long the IRC thread waits on a read before checking it is still needed.
+const THREAD_READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+pub(crate) enum IRCResponse {
   IRCMessage(IRCMessage),
-  //  Error(String),
+  Error(IRCError),
 }
 
+/// Chat connection over twitch's IRC interface.
+///
+/// Reconnects by itself when twitch asks it to or the connection drops,
+/// rejoining the channel it was in.
 pub struct IRCChat {
-  pub client: WebSocket<MaybeTlsStream<TcpStream>>,
+  client: WebSocket<MaybeTlsStream<TcpStream>>,
   pub joined_channel: Option<String>,
   pub user_token: String,
   pub user_login: String,
+  read_timeout: Option<Duration>,
 }
 
-#[derive(Default)]
-pub struct IRCMessage {
-  pub display_name: String,
-  pub moderator: bool,
-  pub subscriber: bool,
-  pub returning_chatter: bool,
-  pub first_time_chatter: bool,
-  pub message: String,
-  pub colour: String,
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IRCSource {
+  pub nick: Option<String>,
+  pub user: Option<String>,
+  pub host: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IRCBadge {
+  pub set_id: String,
+  pub version: String,
+}
+
+/// Position of an emote in the message text, `start` and `end` are
+/// inclusive and count characters, not bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IRCEmote {
+  pub id: String,
+  pub start: usize,
+  pub end: usize,
 }
 
-impl From<&str> for IRCMessage {
-  fn from(value: &str) -> Self {
-    let elements = value.split(";").collect::<Vec<_>>();
+/// A single IRCv3 message, `@tags :source COMMAND params :trailing`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IRCMessage {
+  pub tags: HashMap<String, String>,
+  pub source: Option<IRCSource>,
+  pub command: String,
+  pub params: Vec<String>,
+}
 
+impl IRCMessage {
+  pub fn parse(line: &str) -> Option<IRCMessage> {
+    let mut rest = line.trim_end_matches(['\r', '\n']);
     let mut message = IRCMessage::default();
 
-    if let Some(irc_message) = elements.last() {
-      message.message = irc_message.to_string();
+    if let Some(tagged) = rest.strip_prefix('@') {
+      let (tags, remaining) = tagged.split_once(' ')?;
+      rest = remaining;
+
+      for tag in tags.split(';').filter(|tag| !tag.is_empty()) {
+        let (key, value) = tag.split_once('=').unwrap_or((tag, ""));
+        message
+          .tags
+          .insert(key.to_owned(), unescape_tag_value(value));
+      }
     }
 
-    for element in elements {
-      if element.contains("first-msg") {
-        if let Some(number_element) = element.split("=").last() {
-          if let Ok(first_time) = number_element.parse::<u32>() {
-            message.first_time_chatter = first_time == 1;
-          }
-        }
+    rest = rest.trim_start_matches(' ');
+
+    if let Some(sourced) = rest.strip_prefix(':') {
+      let (source, remaining) = sourced.split_once(' ')?;
+      rest = remaining;
+
+      let (nick, host) = match source.split_once('@') {
+        Some((nick, host)) => (Some(nick), host),
+        None => (None, source),
+      };
+      let (nick, user) = match nick.and_then(|nick| nick.split_once('!')) {
+        Some((nick, user)) => (Some(nick.to_owned()), Some(user.to_owned())),
+        None => (nick.map(str::to_owned), None),
+      };
+
+      message.source = Some(IRCSource {
+        nick,
+        user,
+        host: host.to_owned(),
+      });
+    }
+
+    rest = rest.trim_start_matches(' ');
+
+    let (command, mut params) = rest.split_once(' ').unwrap_or((rest, ""));
+    if command.is_empty() {
+      return None;
+    }
+    message.command = command.to_owned();
+
+    loop {
+      params = params.trim_start_matches(' ');
+      if params.is_empty() {
+        break;
       }
 
-      if element.contains("mod=") {
-        if let Some(number_element) = element.split("=").last() {
-          if let Ok(moderator) = number_element.parse::<u32>() {
-            message.moderator = moderator == 1;
-          }
-        }
+      if let Some(trailing) = params.strip_prefix(':') {
+        message.params.push(trailing.to_owned());
+        break;
       }
 
-      if element.contains("display-name") {
-        if let Some(display_name) = element.split("=").last() {
-          message.display_name = display_name.to_owned();
-        }
+      let (param, remaining) = params.split_once(' ').unwrap_or((params, ""));
+      message.params.push(param.to_owned());
+      params = remaining;
+    }
+
+    Some(message)
+  }
+
+  pub fn tag(&self, key: &str) -> Option<&str> {
+    self
+      .tags
+      .get(key)
+      .map(String::as_str)
+      .filter(|value| !value.is_empty())
+  }
+
+  /// Tags such as `mod` and `first-msg` are sent as `0` or `1`.
+  pub fn tag_flag(&self, key: &str) -> bool {
+    self.tag(key) == Some("1")
+  }
+
+  /// The channel the message was sent to, without the leading `#`.
+  pub fn channel(&self) -> Option<&str> {
+    self
+      .params
+      .first()
+      .and_then(|channel| channel.strip_prefix('#'))
+  }
+
+  /// The chat text of a PRIVMSG or USERNOTICE, the last parameter.
+  pub fn text(&self) -> Option<&str> {
+    if self.params.len() < 2 {
+      return None;
+    }
+
+    self.params.last().map(|text| {
+      // /me messages are wrapped as a CTCP ACTION
+      text
+        .strip_prefix("\u{1}ACTION ")
+        .and_then(|text| text.strip_suffix('\u{1}'))
+        .unwrap_or(text)
+    })
+  }
+
+  pub fn is_action(&self) -> bool {
+    self
+      .params
+      .last()
+      .is_some_and(|text| text.starts_with("\u{1}ACTION "))
+  }
+
+  pub fn id(&self) -> Option<&str> {
+    self.tag("id")
+  }
+
+  pub fn user_id(&self) -> Option<&str> {
+    self.tag("user-id")
+  }
+
+  pub fn room_id(&self) -> Option<&str> {
+    self.tag("room-id")
+  }
+
+  pub fn login(&self) -> Option<&str> {
+    self.tag("login").or(
+      self
+        .source
+        .as_ref()
+        .and_then(|source| source.nick.as_deref()),
+    )
+  }
+
+  pub fn display_name(&self) -> Option<&str> {
+    self.tag("display-name")
+  }
+
+  pub fn colour(&self) -> Option<&str> {
+    self.tag("color")
+  }
+
+  pub fn moderator(&self) -> bool {
+    self.tag_flag("mod")
+  }
+
+  pub fn subscriber(&self) -> bool {
+    self.tag_flag("subscriber")
+  }
+
+  pub fn first_time_chatter(&self) -> bool {
+    self.tag_flag("first-msg")
+  }
+
+  pub fn returning_chatter(&self) -> bool {
+    self.tag_flag("returning-chatter")
+  }
+
+  pub fn bits(&self) -> Option<u32> {
+    self.tag("bits").and_then(|bits| bits.parse().ok())
+  }
+
+  pub fn sent_timestamp(&self) -> Option<u64> {
+    self
+      .tag("tmi-sent-ts")
+      .and_then(|timestamp| timestamp.parse().ok())
+  }
+
+  /// `badges=moderator/1,subscriber/12`
+  pub fn badges(&self) -> Vec<IRCBadge> {
+    self
+      .tag("badges")
+      .map(|badges| {
+        badges
+          .split(',')
+          .filter_map(|badge| badge.split_once('/'))
+          .map(|(set_id, version)| IRCBadge {
+            set_id: set_id.to_owned(),
+            version: version.to_owned(),
+          })
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+
+  /// `emotes=25:0-4,12-16/1902:6-10`, sorted by where they appear in the text.
+  pub fn emotes(&self) -> Vec<IRCEmote> {
+    let mut emotes = self
+      .tag("emotes")
+      .map(|emotes| {
+        emotes
+          .split('/')
+          .filter_map(|emote| emote.split_once(':'))
+          .flat_map(|(id, positions)| {
+            positions.split(',').filter_map(move |position| {
+              let (start, end) = position.split_once('-')?;
+              Some(IRCEmote {
+                id: id.to_owned(),
+                start: start.parse().ok()?,
+                end: end.parse().ok()?,
+              })
+            })
+          })
+          .collect::<Vec<_>>()
+      })
+      .unwrap_or_default();
+
+    emotes.sort_by_key(|emote| emote.start);
+    emotes
+  }
+
+  /// The kind of USERNOTICE or NOTICE, such as `sub`, `raid` or `msg_banned`.
+  pub fn msg_id(&self) -> Option<&str> {
+    self.tag("msg-id")
+  }
+
+  pub fn system_msg(&self) -> Option<&str> {
+    self.tag("system-msg")
+  }
+
+  pub fn reply_parent_msg_id(&self) -> Option<&str> {
+    self.tag("reply-parent-msg-id")
+  }
+
+  pub fn reply_parent_user_id(&self) -> Option<&str> {
+    self.tag("reply-parent-user-id")
+  }
+
+  pub fn reply_parent_user_login(&self) -> Option<&str> {
+    self.tag("reply-parent-user-login")
+  }
+
+  pub fn reply_parent_display_name(&self) -> Option<&str> {
+    self.tag("reply-parent-display-name")
+  }
+
+  pub fn reply_parent_msg_body(&self) -> Option<&str> {
+    self.tag("reply-parent-msg-body")
+  }
+
+  pub fn reply_thread_parent_msg_id(&self) -> Option<&str> {
+    self.tag("reply-thread-parent-msg-id")
+  }
+
+  /// Builds the same [`MessageData`] a channel.chat.message event would have
+  /// from a PRIVMSG, so [`crate::prelude::BTTV`] and
+  /// [`crate::prelude::EmoteBuilder`] work on IRC chat too, anonymous
+  /// connections use [`crate::prelude::EmoteBuilder::build_without_token`].
+  ///
+  /// Fragments only contain text and emotes, IRC doesn't mark mentions or
+  /// cheermotes.
+  pub fn to_message_data(&self) -> Option<MessageData> {
+    if self.command != PRIV_MESSAGE {
+      return None;
+    }
+
+    let text = self.text().unwrap_or_default().to_owned();
+    let channel = self.channel().unwrap_or_default().to_owned();
+    let login = self.login().unwrap_or_default().to_owned();
+
+    let badge_info = self
+      .tag("badge-info")
+      .map(|info| {
+        info
+          .split(',')
+          .filter_map(|badge| badge.split_once('/'))
+          .collect::<HashMap<_, _>>()
+      })
+      .unwrap_or_default();
+    let badges = self
+      .badges()
+      .into_iter()
+      .map(|badge| Badge {
+        info: badge_info
+          .get(badge.set_id.as_str())
+          .map(|info| info.to_string())
+          .unwrap_or_default(),
+        set_id: badge.set_id,
+        id: badge.version,
+      })
+      .collect();
+
+    let message_type = match self.msg_id() {
+      Some("highlighted-message") => MessageType::ChannelPointsHighlighted,
+      Some("skip-subs-mode-message") => MessageType::ChannelPointsSubOnly,
+      Some("user-intro") => MessageType::UserIntro,
+      Some("animated-message") => MessageType::PowerUpsMessageEffect,
+      Some("gigantified-emote-message") => MessageType::PowerUpsGigantifiedEmote,
+      _ => MessageType::Text,
+    };
+
+    let reply = self.reply_parent_msg_id().map(|parent_message_id| {
+      let tag = |key| self.tag(key).unwrap_or_default().to_owned();
+
+      Reply {
+        thread: User {
+          id: tag("reply-thread-parent-user-id"),
+          name: tag("reply-thread-parent-display-name"),
+          login: tag("reply-thread-parent-user-login"),
+        },
+        parent: User {
+          id: tag("reply-parent-user-id"),
+          name: tag("reply-parent-display-name"),
+          login: tag("reply-parent-user-login"),
+        },
+        parent_message_id: parent_message_id.to_owned(),
+        parent_message_body: tag("reply-parent-msg-body"),
+        thread_message_id: tag("reply-thread-parent-msg-id"),
       }
+    });
 
-      if element.contains("returning-chatter") {
-        if let Some(number_element) = element.split("=").last() {
-          if let Ok(returning_chatter) = number_element.parse::<u32>() {
-            message.returning_chatter = returning_chatter == 1;
-          }
-        }
+    Some(MessageData {
+      broadcaster: User {
+        id: self.room_id().unwrap_or_default().to_owned(),
+        name: channel.clone(),
+        login: channel,
+      },
+      chatter: User {
+        id: self.user_id().unwrap_or_default().to_owned(),
+        name: self.display_name().unwrap_or(&login).to_owned(),
+        login,
+      },
+      message_id: self.id().unwrap_or_default().to_owned(),
+      message: Message {
+        fragments: self.fragments(&text),
+        text,
+      },
+      colour: self.colour().unwrap_or_default().to_owned(),
+      badges,
+      message_type,
+      cheer: self.bits().map(|bits| Cheer { bits }),
+      reply,
+      channel_points_custom_reward_id: self.tag("custom-reward-id").map(str::to_owned),
+      channel_points_animation_id: self.tag("animation-id").map(str::to_owned),
+      source_broadcaster: OptionalUser {
+        id: self.tag("source-room-id").map(str::to_owned),
+        name: None,
+        login: None,
+      },
+      source_message_id: self.tag("source-id").map(str::to_owned),
+      source_badges: None,
+      is_source_only: self.tag("source-only").map(|only| only == "1"),
+      first_time_chatter: self.first_time_chatter(),
+      returning_chatter: self.returning_chatter(),
+      moderator: self.moderator(),
+    })
+  }
+
+  // Splits the text around the emotes tag positions, which count characters.
+  fn fragments(&self, text: &str) -> Vec<Fragments> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut fragments = Vec::new();
+    let mut position = 0;
+
+    let fragment = |kind, text: String, emote| Fragments {
+      kind,
+      text,
+      cheermote: None,
+      emote,
+      mention: None,
+    };
+
+    for emote in self.emotes() {
+      if emote.start < position || emote.end >= chars.len() {
+        continue;
       }
 
-      if element.contains("subscriber=") {
-        if let Some(number_element) = element.split("=").last() {
-          if let Ok(subscriber) = number_element.parse::<u32>() {
-            message.subscriber = subscriber == 1;
-          }
-        }
+      if emote.start > position {
+        fragments.push(fragment(
+          FragmentType::Text,
+          chars[position..emote.start].iter().collect(),
+          None,
+        ));
       }
 
-      if element.contains("color") {
-        if let Some(colour) = element.split("=").last() {
-          message.colour = colour.to_owned();
+      fragments.push(fragment(
+        FragmentType::Emote,
+        chars[emote.start..=emote.end].iter().collect(),
+        Some(Emote {
+          id: emote.id,
+          emote_set_id: String::new(),
+          owner_id: None,
+          format: None,
+        }),
+      ));
+      position = emote.end + 1;
+    }
+
+    if position < chars.len() {
+      fragments.push(fragment(
+        FragmentType::Text,
+        chars[position..].iter().collect(),
+        None,
+      ));
+    }
+
+    fragments
+  }
+}
+
+fn unescape_tag_value(value: &str) -> String {
+  let mut unescaped = String::with_capacity(value.len());
+  let mut chars = value.chars();
+
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      unescaped.push(c);
+      continue;
+    }
+
+    match chars.next() {
+      Some(':') => unescaped.push(';'),
+      Some('s') => unescaped.push(' '),
+      Some('r') => unescaped.push('\r'),
+      Some('n') => unescaped.push('\n'),
+      Some(other) => unescaped.push(other),
+      // A lone trailing backslash is dropped
+      None => {}
+    }
+  }
+
+  unescaped
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IRCClearChat {
+  pub channel: String,
+  /// None when the whole chat was cleared
+  pub target_user_id: Option<String>,
+  pub target_user_login: Option<String>,
+  /// Timeout length in seconds, None for a permanent ban
+  pub ban_duration: Option<u32>,
+}
+
+impl IRCClearChat {
+  pub fn is_clear_all(&self) -> bool {
+    self.target_user_login.is_none()
+  }
+
+  pub fn is_ban(&self) -> bool {
+    !self.is_clear_all() && self.ban_duration.is_none()
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IRCClearMsg {
+  pub channel: String,
+  pub login: Option<String>,
+  pub target_msg_id: Option<String>,
+  pub text: String,
+}
+
+/// ROOMSTATE is sent in full on join and then only with the setting that
+/// changed, so every setting is optional.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IRCRoomState {
+  pub channel: String,
+  pub room_id: Option<String>,
+  pub emote_only: Option<bool>,
+  /// Minutes followed before chatting, -1 when followers only is off
+  pub followers_only: Option<i32>,
+  pub unique_chat: Option<bool>,
+  /// Seconds between messages, 0 when slow mode is off
+  pub slow: Option<u32>,
+  pub subs_only: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IRCUserState {
+  pub channel: String,
+  pub display_name: Option<String>,
+  pub colour: Option<String>,
+  pub badges: Vec<IRCBadge>,
+  pub moderator: bool,
+  pub emote_sets: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IRCNotice {
+  pub channel: Option<String>,
+  pub msg_id: Option<String>,
+  pub text: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IRCMembership {
+  pub channel: String,
+  pub login: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IRCEvent {
+  PrivMsg(IRCMessage),
+  UserNotice(IRCMessage),
+  ClearChat(IRCClearChat),
+  ClearMsg(IRCClearMsg),
+  RoomState(IRCRoomState),
+  UserState(IRCUserState),
+  Notice(IRCNotice),
+  Join(IRCMembership),
+  Part(IRCMembership),
+  /// Twitch is about to restart the server, [`IRCChat`] reconnects after
+  /// this is received.
+  Reconnect,
+  Other(IRCMessage),
+}
+
+impl From<IRCMessage> for IRCEvent {
+  fn from(message: IRCMessage) -> IRCEvent {
+    let channel = message.channel().unwrap_or_default().to_owned();
+
+    match message.command.as_str() {
+      PRIV_MESSAGE => IRCEvent::PrivMsg(message),
+      "USERNOTICE" => IRCEvent::UserNotice(message),
+      "CLEARCHAT" => IRCEvent::ClearChat(IRCClearChat {
+        target_user_id: message.tag("target-user-id").map(str::to_owned),
+        target_user_login: message.params.get(1).cloned(),
+        ban_duration: message
+          .tag("ban-duration")
+          .and_then(|duration| duration.parse().ok()),
+        channel,
+      }),
+      "CLEARMSG" => IRCEvent::ClearMsg(IRCClearMsg {
+        login: message.tag("login").map(str::to_owned),
+        target_msg_id: message.tag("target-msg-id").map(str::to_owned),
+        text: message.text().unwrap_or_default().to_owned(),
+        channel,
+      }),
+      "ROOMSTATE" => {
+        let flag = |key| message.tag(key).map(|value| value == "1");
+        let number = |key| message.tag(key).and_then(|value| value.parse().ok());
+
+        IRCEvent::RoomState(IRCRoomState {
+          room_id: message.room_id().map(str::to_owned),
+          emote_only: flag("emote-only"),
+          followers_only: number("followers-only"),
+          unique_chat: flag("r9k"),
+          slow: number("slow").and_then(|slow: i32| u32::try_from(slow).ok()),
+          subs_only: flag("subs-only"),
+          channel,
+        })
+      }
+      "USERSTATE" => IRCEvent::UserState(IRCUserState {
+        display_name: message.display_name().map(str::to_owned),
+        colour: message.colour().map(str::to_owned),
+        badges: message.badges(),
+        moderator: message.moderator(),
+        emote_sets: message
+          .tag("emote-sets")
+          .map(|sets| sets.split(',').map(str::to_owned).collect())
+          .unwrap_or_default(),
+        channel,
+      }),
+      "NOTICE" => IRCEvent::Notice(IRCNotice {
+        channel: message.channel().map(str::to_owned),
+        msg_id: message.msg_id().map(str::to_owned),
+        text: message.params.last().cloned().unwrap_or_default(),
+      }),
+      JOIN | PART => {
+        let membership = IRCMembership {
+          login: message.login().unwrap_or_default().to_owned(),
+          channel,
+        };
+
+        if message.command == JOIN {
+          IRCEvent::Join(membership)
+        } else {
+          IRCEvent::Part(membership)
         }
       }
+      "RECONNECT" => IRCEvent::Reconnect,
+      _ => IRCEvent::Other(message),
     }
-
-    message
   }
 }
 
 impl IRCChat {
-  pub fn new(user_login: &str, user_token: &str) -> IRCChat {
-    dbg!(&IRC_URL);
-    let (mut irc_client, _) = connect(IRC_URL).unwrap();
+  pub fn new(user_login: &str, user_token: &str) -> Result<IRCChat, IRCError> {
+    Ok(IRCChat {
+      client: IRCChat::open_connection(user_login, user_token)?,
+      joined_channel: None,
+      user_token: user_token.to_owned(),
+      user_login: user_login.to_owned(),
+      read_timeout: None,
+    })
+  }
+
+  /// Read only connection that needs no token, twitch treats any
+  /// `justinfan` nick as an anonymous viewer. Sending messages does nothing.
+  pub fn anonymous() -> Result<IRCChat, IRCError> {
+    let nanos = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap_or_default()
+      .subsec_nanos();
+
+    IRCChat::new(&format!("justinfan{}", 10000 + nanos % 90000), "")
+  }
+
+  pub fn is_anonymous(&self) -> bool {
+    self.user_token.is_empty()
+  }
+
+  /// Stops [`IRCChat::recv_message`] blocking for longer than `timeout`,
+  /// kept across reconnects.
+  pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+    self.read_timeout = timeout;
+    set_read_timeout(&mut self.client, timeout);
+  }
+
+  fn open_connection(
+    user_login: &str,
+    user_token: &str,
+  ) -> Result<WebSocket<MaybeTlsStream<TcpStream>>, IRCError> {
+    let (mut irc_client, _) =
+      connect(IRC_URL).map_err(|e| IRCError::ConnectionFailed(e.to_string()))?;
 
     let _ = irc_client.send(NetworkMessage::text(
       "CAP REQ :twitch.tv/membership twitch.tv/tags twitch.tv/commands",
     ));
 
-    let _ = irc_client.send(NetworkMessage::text(format!(
-      "{} oauth:{}",
-      PASS, &user_token
-    )));
+    if !user_token.is_empty() {
+      let _ = irc_client.send(NetworkMessage::text(format!(
+        "{} oauth:{}",
+        PASS, &user_token
+      )));
+    }
     let _ = irc_client.send(NetworkMessage::text(format!("{} {}", NICK, &user_login)));
 
-    let mut welcome_recieved = false;
-    while !welcome_recieved {
-      if let Ok(NetworkMessage::Text(text)) = &irc_client.read() {
-        welcome_recieved = text.as_str().contains("Welcome");
+    loop {
+      let text = match irc_client.read() {
+        Ok(NetworkMessage::Text(text)) => text,
+        Ok(_) => continue,
+        Err(e) => return Err(IRCError::ConnectionFailed(e.to_string())),
+      };
+
+      for message in text.as_str().split("\r\n").filter_map(IRCMessage::parse) {
+        match message.command.as_str() {
+          // RPL_WELCOME
+          "001" => return Ok(irc_client),
+          "NOTICE" => {
+            let notice = message.params.last().cloned().unwrap_or_default();
+            if notice.contains("authentication failed") ||
+              notice.contains("Improperly formatted auth")
+            {
+              return Err(IRCError::AuthenticationFailed(notice));
+            }
+          }
+          _ => {}
+        }
       }
     }
+  }
 
-    IRCChat {
-      client: irc_client,
-      joined_channel: None,
-      user_token: user_token.to_owned(),
-      user_login: user_login.to_owned(),
+  /// Drops the current connection and connects again, rejoining the channel.
+  pub fn reconnect(&mut self) -> Result<(), IRCError> {
+    let _ = self.client.close(None);
+
+    self.client = IRCChat::open_connection(&self.user_login, &self.user_token)?;
+    set_read_timeout(&mut self.client, self.read_timeout);
+
+    if let Some(channel) = self.joined_channel.take() {
+      self.join_channel(channel);
+    }
+
+    Ok(())
+  }
+
+  /// Keeps trying to reconnect, doubling the wait between attempts up to a minute.
+  ///
+  /// Gives up when twitch rejects the token, retrying with it won't work.
+  fn reconnect_with_backoff(&mut self) -> Result<(), IRCError> {
+    let mut delay = Duration::from_secs(1);
+
+    loop {
+      #[cfg(feature = "logging")]
+      warn!("IRC: attempting reconnect in {:?}", delay);
+      thread::sleep(delay);
+
+      match self.reconnect() {
+        Ok(()) => return Ok(()),
+        Err(e @ IRCError::AuthenticationFailed(_)) => return Err(e),
+        Err(_e) => {
+          #[cfg(feature = "logging")]
+          error!("IRC: reconnect failed: {:?}", _e);
+          delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+      }
     }
   }
 
   pub fn join_channel<S: Into<String>>(&mut self, channel_name: S) {
     let channel = channel_name.into();
-    let _ = self.client.send(NetworkMessage::text(format!(
-      "{} #{}",
-      JOIN,
-      channel.to_owned()
-    )));
+    let _ = self
+      .client
+      .send(NetworkMessage::text(format!("{} #{}", JOIN, channel)));
 
     self.joined_channel = Some(channel);
   }
 
-  pub fn recv_message(&mut self) -> Option<IRCMessage> {
-    match self.client.read() {
-      Ok(message) => match message {
-        NetworkMessage::Text(text) => {
-          if text.as_str().contains("PING :tmi.twitch.tv") {
-            #[cfg(feature = "logging")]
-            info!("IRC: ping recieved, sending pong back");
-            let _ = self
-              .client
-              .send(NetworkMessage::Pong(Vec::with_capacity(0).into()));
-            let _ = self
-              .client
-              .send(NetworkMessage::Text("PONG :tmi.twitch.tv".into()));
-            let _ = self.client.send(NetworkMessage::Text("PONG".into()));
-            None
-          } else {
-            Some(IRCMessage::from(text.as_str()))
-          }
-        }
-        NetworkMessage::Ping(data) => {
-          #[cfg(feature = "logging")]
-          info!("IRC: ping recieved, sending pong back");
-          let _ = self.client.send(NetworkMessage::Pong(data));
-          None
-        }
-        NetworkMessage::Close(_) => {
-          #[cfg(feature = "logging")]
-          error!("IRC: connection Closed");
-          None
-        }
-        _ => None,
-      },
-      Err(Error::Protocol(ProtocolError::ResetWithoutClosingHandshake)) => {
+  pub fn part_channel(&mut self) {
+    if let Some(channel) = self.joined_channel.take() {
+      let _ = self
+        .client
+        .send(NetworkMessage::text(format!("{} #{}", PART, channel)));
+    }
+  }
+
+  /// Blocks until the next websocket frame, a single frame can carry several
+  /// IRC lines so all of them are returned.
+  ///
+  /// PINGs are answered here, and a dropped connection or RECONNECT is
+  /// handled by reconnecting before returning.
+  ///
+  /// Fails with [`IRCError::AuthenticationFailed`] when the token is rejected
+  /// while reconnecting, set a fresh [`IRCChat::user_token`] and call
+  /// [`IRCChat::reconnect`] to carry on.
+  pub fn recv_message(&mut self) -> Result<Vec<IRCMessage>, IRCError> {
+    let text = match self.client.read() {
+      Ok(NetworkMessage::Text(text)) => text,
+      Ok(NetworkMessage::Ping(data)) => {
+        let _ = self.client.send(NetworkMessage::Pong(data));
+        return Ok(Vec::new());
+      }
+      Ok(NetworkMessage::Close(_)) => {
         #[cfg(feature = "logging")]
-        error!("Protocol error: Reset without closing handshake");
+        error!("IRC: connection closed");
+        self.reconnect_with_backoff()?;
+        return Ok(Vec::new());
+      }
+      Ok(_) => return Ok(Vec::new()),
+      Err(Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+        return Ok(Vec::new());
+      }
+      Err(_e) => {
         #[cfg(feature = "logging")]
-        warn!("Restarting IRC connection");
-        let _ = self.client.close(None);
+        error!("IRC: read error: {:?}", _e);
+        self.reconnect_with_backoff()?;
+        return Ok(Vec::new());
+      }
+    };
 
-        #[cfg(feature = "logging")]
-        warn!("Close connection requested");
-        thread::sleep(Duration::from_secs(5));
+    let mut messages = Vec::new();
+    let mut should_reconnect = false;
 
-        #[cfg(feature = "logging")]
-        warn!("Attempting reconnect with IRC");
-        *self = IRCChat::new(&self.user_login, &self.user_token);
-        None
+    for message in text.as_str().split("\r\n").filter_map(IRCMessage::parse) {
+      match message.command.as_str() {
+        "PING" => {
+          #[cfg(feature = "logging")]
+          info!("IRC: ping recieved, sending pong back");
+          let _ = self.client.send(NetworkMessage::text(format!(
+            "PONG :{}",
+            message
+              .params
+              .last()
+              .map(String::as_str)
+              .unwrap_or("tmi.twitch.tv")
+          )));
+        }
+        "RECONNECT" => {
+          should_reconnect = true;
+          messages.push(message);
+        }
+        _ => messages.push(message),
       }
-      Err(e) => {
-        #[cfg(feature = "logging")]
-        error!("IRC: Error: {:?}", e);
-        None
+    }
+
+    if should_reconnect {
+      #[cfg(feature = "logging")]
+      warn!("IRC: twitch requested a reconnect");
+      match self.reconnect() {
+        Ok(()) => {}
+        Err(e @ IRCError::AuthenticationFailed(_)) => return Err(e),
+        Err(_) => self.reconnect_with_backoff()?,
       }
     }
+
+    Ok(messages)
+  }
+
+  /// Same as [`IRCChat::recv_message`] but sorted into [`IRCEvent`]s.
+  pub fn recv_events(&mut self) -> Result<Vec<IRCEvent>, IRCError> {
+    Ok(
+      self
+        .recv_message()?
+        .into_iter()
+        .map(IRCEvent::from)
+        .collect(),
+    )
   }
 
   pub fn send_message<S: Into<String>>(&mut self, m: S) {
+    if self.is_anonymous() {
+      #[cfg(feature = "logging")]
+      warn!("IRC: anonymous connections can't send messages");
+      return;
+    }
+
     if let Some(channel) = &self.joined_channel {
       let m = m.into();
       let _ = self.client.send(NetworkMessage::text(format!(
@@ -204,12 +877,219 @@ impl IRCChat {
       )));
     }
   }
+
+  pub fn reply_to_message<S: Into<String>>(&mut self, reply_to_message_id: &str, m: S) {
+    if self.is_anonymous() {
+      #[cfg(feature = "logging")]
+      warn!("IRC: anonymous connections can't send messages");
+      return;
+    }
+
+    if let Some(channel) = &self.joined_channel {
+      let m = m.into();
+      let _ = self.client.send(NetworkMessage::text(format!(
+        "@reply-parent-msg-id={} {} #{} :{}",
+        reply_to_message_id, PRIV_MESSAGE, channel, m
+      )));
+    }
+  }
 }
 
-pub fn irc_thread(mut irc: IRCChat, message_sender: SyncSender<IRCResponse>) {
+/// Runs until the token is rejected or `should_quit_receiver` says to stop,
+/// which includes its sender being dropped.
+pub(crate) fn irc_thread(
+  mut irc: IRCChat,
+  message_sender: SyncSender<IRCResponse>,
+  should_quit_receiver: SyncReceiver<bool>,
+) {
+  // Reads time out so a quiet chat doesn't keep the thread alive forever
+  irc.set_read_timeout(Some(THREAD_READ_TIMEOUT));
+
   loop {
-    if let Some(irc_msg) = irc.recv_message() {
-      let _ = message_sender.send(IRCResponse::IRCMessage(irc_msg));
+    if let Ok(true) | Err(TryRecvError::Disconnected) = should_quit_receiver.try_recv() {
+      return;
     }
+
+    let messages = match irc.recv_message() {
+      Ok(messages) => messages,
+      Err(e) => {
+        #[cfg(feature = "logging")]
+        error!("IRC: giving up: {:?}", e);
+        let _ = message_sender.send(IRCResponse::Error(e));
+        return;
+      }
+    };
+
+    for irc_msg in messages {
+      if message_sender
+        .send(IRCResponse::IRCMessage(irc_msg))
+        .is_err()
+      {
+        // Receiver is gone, nothing left to deliver to
+        return;
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unescapes_tag_values() {
+    assert_eq!(unescape_tag_value(r"hello\sthere"), "hello there");
+    assert_eq!(unescape_tag_value(r"a\:b"), "a;b");
+    assert_eq!(unescape_tag_value(r"back\\slash"), r"back\slash");
+    assert_eq!(unescape_tag_value(r"line\r\n"), "line\r\n");
+    assert_eq!(unescape_tag_value(r"trailing\"), "trailing");
+    assert_eq!(unescape_tag_value(r"\q"), "q");
+  }
+
+  #[test]
+  fn parses_privmsg_with_tags_and_source() {
+    let message = IRCMessage::parse(
+      "@badges=moderator/1,subscriber/12;display-name=Cool_User;mod=1;system-msg=hi\\sthere\\:) \
+       :cool_user!cool_user@cool_user.tmi.twitch.tv PRIVMSG #channel :hello world\r\n",
+    )
+    .unwrap();
+
+    assert_eq!(message.command, PRIV_MESSAGE);
+    assert_eq!(message.params, vec!["#channel", "hello world"]);
+    assert_eq!(message.channel(), Some("channel"));
+    assert_eq!(message.text(), Some("hello world"));
+    assert_eq!(message.display_name(), Some("Cool_User"));
+    assert_eq!(message.system_msg(), Some("hi there;)"));
+    assert!(message.moderator());
+    assert_eq!(
+      message.source,
+      Some(IRCSource {
+        nick: Some(String::from("cool_user")),
+        user: Some(String::from("cool_user")),
+        host: String::from("cool_user.tmi.twitch.tv"),
+      })
+    );
+    assert_eq!(message.login(), Some("cool_user"));
+    assert_eq!(
+      message.badges(),
+      vec![
+        IRCBadge {
+          set_id: String::from("moderator"),
+          version: String::from("1"),
+        },
+        IRCBadge {
+          set_id: String::from("subscriber"),
+          version: String::from("12"),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn parses_server_source_without_nick() {
+    let message = IRCMessage::parse(":tmi.twitch.tv 001 justinfan123 :Welcome, GLHF!").unwrap();
+
+    assert_eq!(message.command, "001");
+    assert_eq!(
+      message.source,
+      Some(IRCSource {
+        nick: None,
+        user: None,
+        host: String::from("tmi.twitch.tv"),
+      })
+    );
+    assert_eq!(message.params, vec!["justinfan123", "Welcome, GLHF!"]);
+  }
+
+  #[test]
+  fn parses_ping_without_source() {
+    let message = IRCMessage::parse("PING :tmi.twitch.tv").unwrap();
+
+    assert_eq!(message.command, "PING");
+    assert!(message.source.is_none());
+    assert!(message.tags.is_empty());
+    assert_eq!(message.params, vec!["tmi.twitch.tv"]);
+    assert_eq!(message.text(), None);
+  }
+
+  #[test]
+  fn rejects_lines_without_a_command() {
+    assert!(IRCMessage::parse("").is_none());
+    assert!(IRCMessage::parse("@id=1").is_none());
+    assert!(IRCMessage::parse(":tmi.twitch.tv").is_none());
+  }
+
+  #[test]
+  fn strips_me_action() {
+    let message = IRCMessage::parse(
+      ":cool_user!cool_user@cool_user.tmi.twitch.tv PRIVMSG #channel :\u{1}ACTION waves\u{1}",
+    )
+    .unwrap();
+
+    assert!(message.is_action());
+    assert_eq!(message.text(), Some("waves"));
+    assert_eq!(message.to_message_data().unwrap().message.text, "waves");
+  }
+
+  #[test]
+  fn fragments_count_emote_positions_in_characters() {
+    // The emoji is 4 bytes but a single character
+    let message = IRCMessage::parse(
+      "@emotes=25:2-6,14-18/1902:8-12 :cool_user!cool_user@cool_user.tmi.twitch.tv \
+       PRIVMSG #channel :\u{1F600} Kappa Keepo Kappa!",
+    )
+    .unwrap();
+
+    let fragments = message.fragments(message.text().unwrap());
+    let texts = fragments
+      .iter()
+      .map(|fragment| fragment.text.as_str())
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      texts,
+      vec!["\u{1F600} ", "Kappa", " ", "Keepo", " ", "Kappa", "!"]
+    );
+    assert!(matches!(fragments[1].kind, FragmentType::Emote));
+    assert_eq!(fragments[1].emote.as_ref().unwrap().id, "25");
+    assert_eq!(fragments[3].emote.as_ref().unwrap().id, "1902");
+    assert!(matches!(fragments[6].kind, FragmentType::Text));
+  }
+
+  #[test]
+  fn fragments_skip_out_of_range_emotes() {
+    let message = IRCMessage::parse("@emotes=25:0-4,3-20 PRIVMSG #channel :Kappa hi").unwrap();
+
+    let fragments = message.fragments(message.text().unwrap());
+
+    assert_eq!(fragments.len(), 2);
+    assert_eq!(fragments[0].text, "Kappa");
+    assert_eq!(fragments[1].text, " hi");
+  }
+
+  #[test]
+  fn clearchat_for_a_timeout_and_a_full_clear() {
+    let timeout = IRCEvent::from(
+      IRCMessage::parse(
+        "@ban-duration=600;room-id=1337;target-user-id=42 :tmi.twitch.tv CLEARCHAT #channel :cool_user",
+      )
+      .unwrap(),
+    );
+    let IRCEvent::ClearChat(timeout) = timeout else {
+      panic!("expected a CLEARCHAT");
+    };
+    assert_eq!(timeout.channel, "channel");
+    assert_eq!(timeout.target_user_login.as_deref(), Some("cool_user"));
+    assert_eq!(timeout.target_user_id.as_deref(), Some("42"));
+    assert_eq!(timeout.ban_duration, Some(600));
+    assert!(!timeout.is_ban());
+
+    let clear =
+      IRCEvent::from(IRCMessage::parse("@room-id=1337 :tmi.twitch.tv CLEARCHAT #channel").unwrap());
+    let IRCEvent::ClearChat(clear) = clear else {
+      panic!("expected a CLEARCHAT");
+    };
+    assert!(clear.is_clear_all());
+    assert!(!clear.is_ban());
   }
 }
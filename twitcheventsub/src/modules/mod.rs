@@ -1,9 +1,16 @@
 pub mod bttv;
+pub mod chat_correlation;
+pub mod chat_queue;
 pub mod consts;
 pub mod emotebuilder;
 pub mod errors;
 pub mod eventsub;
+pub mod hype_train;
 pub mod irc_bot;
+pub mod roster;
+pub mod shared_chat;
+pub mod stream_session;
+pub mod viewer_count;
 
 #[cfg(feature = "bevy")]
 pub mod bevy;
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use twitcheventsub_api::TwitchApiError;
+use twitcheventsub_structs::prelude::{ModeratorAction, TwitchEvent, User};
+use twitcheventsub_tokens::TokenHandler;
+
+/// Local copy of a channels moderators and vips, so permission checks
+/// don't need to hit the api for every chat message.
+///
+/// Seeded from Get Moderators and Get VIPs, then kept up to date from the
+/// channel.moderator.add/remove and channel.vip.add/remove events.
+#[derive(Clone, Debug, Default)]
+pub struct RosterCache {
+  broadcaster_id: String,
+  moderators: HashMap<String, User>,
+  vips: HashMap<String, User>,
+}
+
+impl RosterCache {
+  pub fn new(
+    tokens: &mut TokenHandler,
+    broadcaster_id: &str,
+  ) -> Result<RosterCache, TwitchApiError> {
+    let mut roster = RosterCache {
+      broadcaster_id: broadcaster_id.to_owned(),
+      ..Default::default()
+    };
+    roster.refresh(tokens)?;
+
+    Ok(roster)
+  }
+
+  /// Throws away the current roster and fetches it again from twitch.
+  pub fn refresh(&mut self, tokens: &mut TokenHandler) -> Result<(), TwitchApiError> {
+    let moderators = tokens.get_moderators_all(&self.broadcaster_id)?;
+    let vips = tokens.get_vips_all(&self.broadcaster_id)?;
+
+    self.moderators = moderators
+      .into_iter()
+      .map(|user| (user.id.clone(), user))
+      .collect();
+    self.vips = vips
+      .into_iter()
+      .map(|user| (user.id.clone(), user))
+      .collect();
+
+    Ok(())
+  }
+
+  /// Feed every received event through here, returns true if the roster changed.
+  ///
+  /// Events for other channels, including ones from shared chat, are ignored.
+  pub fn update(&mut self, event: &TwitchEvent) -> bool {
+    match event {
+      TwitchEvent::ModeratorAdd(data) |
+      TwitchEvent::ModeratorRemove(data) |
+      TwitchEvent::VipAdd(data) |
+      TwitchEvent::VipRemove(data)
+        if data.broadcaster.id != self.broadcaster_id =>
+      {
+        false
+      }
+      TwitchEvent::ModeratorEvent(moderate)
+        if moderate.broadcaster.id != self.broadcaster_id ||
+          moderate
+            .source
+            .id
+            .as_ref()
+            .is_some_and(|source_id| *source_id != self.broadcaster_id) =>
+      {
+        false
+      }
+      TwitchEvent::ModeratorAdd(data) => self.add_moderator(&data.user),
+      TwitchEvent::ModeratorRemove(data) => self.moderators.remove(&data.user.id).is_some(),
+      TwitchEvent::VipAdd(data) => self.add_vip(&data.user),
+      TwitchEvent::VipRemove(data) => self.vips.remove(&data.user.id).is_some(),
+      TwitchEvent::ModeratorEvent(moderate) => match moderate.action {
+        ModeratorAction::Mod => moderate
+          .mod_user
+          .as_ref()
+          .is_some_and(|user| self.add_moderator(user)),
+        ModeratorAction::Unmod => moderate
+          .unmod
+          .as_ref()
+          .is_some_and(|user| self.moderators.remove(&user.id).is_some()),
+        ModeratorAction::Vip => moderate.vip.as_ref().is_some_and(|user| self.add_vip(user)),
+        ModeratorAction::Unvip => moderate
+          .unvip
+          .as_ref()
+          .is_some_and(|user| self.vips.remove(&user.id).is_some()),
+        _ => false,
+      },
+      _ => false,
+    }
+  }
+
+  pub fn is_broadcaster(&self, user_id: &str) -> bool {
+    self.broadcaster_id == user_id
+  }
+
+  pub fn is_moderator(&self, user_id: &str) -> bool {
+    self.moderators.contains_key(user_id)
+  }
+
+  pub fn is_vip(&self, user_id: &str) -> bool {
+    self.vips.contains_key(user_id)
+  }
+
+  /// Broadcaster or moderator, the users allowed to run moderation commands.
+  pub fn can_moderate(&self, user_id: &str) -> bool {
+    self.is_broadcaster(user_id) || self.is_moderator(user_id)
+  }
+
+  pub fn moderators(&self) -> impl Iterator<Item = &User> {
+    self.moderators.values()
+  }
+
+  pub fn vips(&self) -> impl Iterator<Item = &User> {
+    self.vips.values()
+  }
+
+  fn add_moderator(&mut self, user: &User) -> bool {
+    self
+      .moderators
+      .insert(user.id.clone(), user.clone())
+      .is_none()
+  }
+
+  fn add_vip(&mut self, user: &User) -> bool {
+    self.vips.insert(user.id.clone(), user.clone()).is_none()
+  }
+}
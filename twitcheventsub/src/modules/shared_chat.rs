@@ -0,0 +1,62 @@
+use twitcheventsub_structs::prelude::{Broadcaster, SharedChatData, TwitchEvent, User};
+
+/// Keeps track of the current shared chat session from the
+/// channel.shared_chat.begin, update and end events.
+#[derive(Clone, Debug, Default)]
+pub struct SharedChatTracker {
+  session: Option<SharedChatData>,
+}
+
+impl SharedChatTracker {
+  pub fn new() -> SharedChatTracker {
+    SharedChatTracker::default()
+  }
+
+  /// Feed every received event through here, returns true if the session changed.
+  pub fn update(&mut self, event: &TwitchEvent) -> bool {
+    match event {
+      TwitchEvent::SharedChatBegin(data) | TwitchEvent::SharedChatUpdate(data) => {
+        self.session = Some(data.clone());
+        true
+      }
+      TwitchEvent::SharedChatEnd(data) if self.session_id() == Some(data.session_id.as_str()) => {
+        self.session = None;
+        true
+      }
+      _ => false,
+    }
+  }
+
+  pub fn is_active(&self) -> bool {
+    self.session.is_some()
+  }
+
+  pub fn session_id(&self) -> Option<&str> {
+    self
+      .session
+      .as_ref()
+      .map(|session| session.session_id.as_str())
+  }
+
+  pub fn host(&self) -> Option<&User> {
+    self
+      .session
+      .as_ref()
+      .map(|session| &session.host_broadcaster)
+  }
+
+  pub fn participants(&self) -> &[Broadcaster] {
+    self
+      .session
+      .as_ref()
+      .map(|session| session.participants.as_slice())
+      .unwrap_or_default()
+  }
+
+  pub fn is_participant(&self, broadcaster_id: &str) -> bool {
+    self
+      .participants()
+      .iter()
+      .any(|participant| participant.broadcaster_user_id == broadcaster_id)
+  }
+}
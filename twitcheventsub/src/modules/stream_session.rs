@@ -0,0 +1,161 @@
+use std::time::{Duration, SystemTime};
+
+use twitcheventsub_api::TwitchApiError;
+use twitcheventsub_structs::prelude::{
+  StreamSession, TwitchEvent, format_timestamp, parse_timestamp,
+};
+use twitcheventsub_tokens::TokenHandler;
+
+/// Tracks whether the broadcaster is live from the stream.online and
+/// stream.offline events, turning them into [`TwitchEvent::SessionStarted`]
+/// and [`TwitchEvent::SessionEnded`].
+///
+/// Seeded from Get Streams, so starting up mid stream still has a session.
+#[derive(Clone, Debug, Default)]
+pub struct StreamSessionTracker {
+  session: Option<StreamSession>,
+}
+
+impl StreamSessionTracker {
+  pub fn new(
+    tokens: &mut TokenHandler,
+    broadcaster_id: &str,
+  ) -> Result<StreamSessionTracker, TwitchApiError> {
+    let session = tokens
+      .get_streams(broadcaster_id)?
+      .data
+      .into_iter()
+      .next()
+      .map(|stream| StreamSession {
+        id: stream.id,
+        broadcaster: stream.user,
+        started_at: stream.started_at,
+        ended_at: None,
+      });
+
+    Ok(StreamSessionTracker { session })
+  }
+
+  /// Feed every received event through here, returns
+  /// [`TwitchEvent::SessionStarted`] or [`TwitchEvent::SessionEnded`] when
+  /// the session changed.
+  pub fn update(&mut self, event: &TwitchEvent) -> Option<TwitchEvent> {
+    match event {
+      TwitchEvent::StreamOnline(online) => {
+        if self.session_id() == Some(online.id.as_str()) {
+          return None;
+        }
+
+        let session = StreamSession {
+          id: online.id.clone(),
+          broadcaster: online.broadcaster.clone(),
+          started_at: online.started_at.clone().unwrap_or_default(),
+          ended_at: None,
+        };
+        self.session = Some(session.clone());
+
+        Some(TwitchEvent::SessionStarted(session))
+      }
+      TwitchEvent::StreamOffline(_) => self.session.take().map(|mut session| {
+        session.ended_at = Some(format_timestamp(SystemTime::now()));
+        TwitchEvent::SessionEnded(session)
+      }),
+      _ => None,
+    }
+  }
+
+  pub fn is_live(&self) -> bool {
+    self.session.is_some()
+  }
+
+  pub fn session(&self) -> Option<&StreamSession> {
+    self.session.as_ref()
+  }
+
+  pub fn session_id(&self) -> Option<&str> {
+    self.session.as_ref().map(|session| session.id.as_str())
+  }
+
+  /// How long the current session has been live for.
+  pub fn uptime(&self) -> Option<Duration> {
+    self
+      .session
+      .as_ref()
+      .and_then(|session| parse_timestamp(&session.started_at))
+      .and_then(|started_at| SystemTime::now().duration_since(started_at).ok())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use twitcheventsub_structs::prelude::{StreamOfflineData, StreamOnlineData, User};
+
+  use super::*;
+
+  fn broadcaster() -> User {
+    User {
+      id: String::from("1337"),
+      name: String::from("Cool_User"),
+      login: String::from("cool_user"),
+    }
+  }
+
+  fn online(id: &str) -> TwitchEvent {
+    TwitchEvent::StreamOnline(StreamOnlineData {
+      id: String::from(id),
+      broadcaster: broadcaster(),
+      kind: String::from("live"),
+      started_at: Some(String::from("2020-10-11T10:11:12.123Z")),
+    })
+  }
+
+  fn seeded(id: &str) -> StreamSessionTracker {
+    StreamSessionTracker {
+      session: Some(StreamSession {
+        id: String::from(id),
+        broadcaster: broadcaster(),
+        started_at: String::from("2020-10-11T10:11:12.123Z"),
+        ended_at: None,
+      }),
+    }
+  }
+
+  #[test]
+  fn online_for_the_seeded_stream_does_not_start_a_new_session() {
+    let mut tracker = seeded("9001");
+
+    assert!(tracker.update(&online("9001")).is_none());
+    assert_eq!(tracker.session_id(), Some("9001"));
+  }
+
+  #[test]
+  fn online_for_a_new_stream_starts_a_session() {
+    let mut tracker = seeded("9001");
+
+    let Some(TwitchEvent::SessionStarted(session)) = tracker.update(&online("9002")) else {
+      panic!("expected a session to start");
+    };
+    assert_eq!(session.id, "9002");
+    assert_eq!(tracker.session_id(), Some("9002"));
+  }
+
+  #[test]
+  fn offline_ends_the_session_once() {
+    let mut tracker = StreamSessionTracker::default();
+    let offline = TwitchEvent::StreamOffline(StreamOfflineData {
+      broadcaster: broadcaster(),
+    });
+
+    assert!(matches!(
+      tracker.update(&online("9001")),
+      Some(TwitchEvent::SessionStarted(_))
+    ));
+    let Some(TwitchEvent::SessionEnded(session)) = tracker.update(&offline) else {
+      panic!("expected the session to end");
+    };
+    assert_eq!(session.id, "9001");
+    assert!(session.ended_at.is_some());
+    assert!(!tracker.is_live());
+    assert!(tracker.update(&offline).is_none());
+  }
+}
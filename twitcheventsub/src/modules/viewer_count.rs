@@ -0,0 +1,96 @@
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+#[cfg(feature = "logging")]
+use log::warn;
+use twitcheventsub_structs::prelude::{TwitchEvent, ViewerCount};
+use twitcheventsub_tokens::TokenHandler;
+
+/// Twitch caches Get Streams, polling much faster than this rarely sees a
+/// new count.
+pub const DEFAULT_VIEWER_COUNT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Polls Get Streams on its own thread, eventsub has no viewer count event.
+///
+/// Sends [`TwitchEvent::ViewerCountChanged`] whenever the count differs from
+/// the last poll, nothing is sent while the broadcaster is offline. The
+/// thread stops when the poller is dropped.
+#[derive(Debug)]
+pub struct ViewerCountPoller {
+  events: Receiver<TwitchEvent>,
+  stop: Sender<()>,
+  _poll_thread: JoinHandle<()>,
+}
+
+impl ViewerCountPoller {
+  pub fn start(
+    mut tokens: TokenHandler,
+    broadcaster_id: &str,
+    interval: Duration,
+  ) -> ViewerCountPoller {
+    let (event_sender, events) = channel();
+    let (stop, stop_receiver) = channel();
+    let broadcaster_id = broadcaster_id.to_owned();
+
+    let poll_thread = thread::spawn(move || {
+      // stream id and its last viewer count
+      let mut last_count: Option<(String, u32)> = None;
+
+      loop {
+        match tokens.get_stream(&broadcaster_id) {
+          Ok(Some(stream)) => {
+            let previous_viewer_count = last_count
+              .as_ref()
+              .filter(|(stream_id, _)| *stream_id == stream.id)
+              .map(|(_, viewer_count)| *viewer_count);
+
+            if previous_viewer_count != Some(stream.viewer_count) {
+              let event = TwitchEvent::ViewerCountChanged(ViewerCount {
+                stream_id: stream.id.clone(),
+                broadcaster: stream.user,
+                viewer_count: stream.viewer_count,
+                previous_viewer_count,
+              });
+
+              if event_sender.send(event).is_err() {
+                break;
+              }
+            }
+
+            last_count = Some((stream.id, stream.viewer_count));
+          }
+          Ok(None) => last_count = None,
+          Err(_e) => {
+            #[cfg(feature = "logging")]
+            warn!("Failed to poll the viewer count: {:?}", _e);
+          }
+        }
+
+        if !matches!(
+          stop_receiver.recv_timeout(interval),
+          Err(RecvTimeoutError::Timeout)
+        ) {
+          break;
+        }
+      }
+    });
+
+    ViewerCountPoller {
+      events,
+      stop,
+      _poll_thread: poll_thread,
+    }
+  }
+
+  /// Every viewer count change since this was last called.
+  pub fn receive_events(&self) -> Vec<TwitchEvent> {
+    self.events.try_iter().collect()
+  }
+}
+
+impl Drop for ViewerCountPoller {
+  fn drop(&mut self) {
+    let _ = self.stop.send(());
+  }
+}